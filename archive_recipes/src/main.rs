@@ -0,0 +1,21 @@
+extern crate hoffman;
+
+use std::env;
+use hoffman::archive::*;
+
+/// Collects the recipe JSON files of directories into one archive.
+///
+/// Usage: `archive_recipes <archive directory> <archive name> <directory>..`
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 3 {
+        println!("Usage: archive_recipes <archive directory> <archive name> <directory>..");
+        return
+    }
+    let mut archive = RecipeArchive::open_or_create(&args[0], &args[1]).expect("Error opening archive.");
+    for directory in &args[2..] {
+        let inserted = archive.import_directory(directory).expect("Error importing recipes.");
+        println!("{}: {} recipes inserted.", directory, inserted);
+    }
+    println!("{} recipes in {}/{}.jsonl.", archive.len(), args[0], args[1]);
+}
@@ -6,19 +6,17 @@ use hoffman::*;
 const N: usize = 3;
 
 fn main() {
-    let dimension_tuples = vec!(
+    let dimension_tuples: Vec<DimensionTuple> = vec!(
         vec!(4, 5, 6),
         //vec!(6, 5, 4)
-    );
+    ).into_iter().map(|sides| DimensionTuple::degenerate(sides).unwrap_or_else(|error| panic!("{}", error))).collect();
     println!("Dimension Tuples: {:?}", dimension_tuples);
 
-    assert!(dimension_tuples.iter().all(|dimension_tuple| utils::list_has_unique_sums(dimension_tuple)), "Dimension tuple does not have unique sums.");
-
     println!("Will determine recipes.");
     let now = Instant::now();
     let recipes = backtrack_cubes(&dimension_tuples);
     println!("Total recipes count: {:?}", recipes.len());
-    let recipes = Recipe::find_unique(recipes);
+    let recipes = Recipe::find_unique_for(recipes, &dimension_tuples);
     println!("Total unique recipes count: {:?}", recipes.len());
 
     let name = format!("3d-universal-recipes");
@@ -48,10 +46,8 @@ fn backtrack_cubes(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
     let mut iteration: usize = 0;
     let mut successes: usize = 0;
 
-    let max_tries = N * (N - 1) * (N - 2);
-
-    let indices: Vec<usize> = (0..N).collect();
-    let perms = combinatorics::permutations(&indices.as_slice(), N);
+    let perms = recipe_builder.orientations();
+    let max_tries = perms.len();
 
     loop {
         iteration += 1;
@@ -1,23 +1,33 @@
 extern crate hoffman;
 
+use std::env;
 use std::time::Instant;
 use hoffman::*;
+use hoffman::signatures::*;
 
 const N: usize = 4;
 
 fn main() {
-    let dimension_tuples = vec!(
-        vec!(8, 9, 10, 12),  // Wide
-        vec!(10, 12, 13, 14) // Narrow
-    );
+    // With `--universal` the tuples represent every realisable order of the
+    // subset sums, so a packing found for all of them is universal.
+    let universal = env::args().skip(1).any(|arg| arg == "--universal");
+    let dimension_tuples: Vec<DimensionTuple> = if universal {
+        SignatureTable::cached(N, 60, &String::from("exports/signatures")).representatives()
+    } else {
+        vec!(
+            vec!(8, 9, 10, 12),  // Wide
+            vec!(10, 12, 13, 14) // Narrow
+        ).into_iter().map(|sides| DimensionTuple::degenerate(sides).unwrap_or_else(|error| panic!("{}", error))).collect()
+    };
 
     println!("Dimension tuples: {:?}", dimension_tuples);
 
-    assert!(dimension_tuples.iter().all(|dimension_tuple| utils::list_has_unique_sums(&dimension_tuple)), "Dimension tuple does not have unique sums.");
-
     println!("Will determine packings.");
     let now = Instant::now();
     let recipe = backtrack_tesseracts(&dimension_tuples);
+    if universal {
+        assert!(recipe.is_universal(), "Packing found for every signature is not universal.");
+    }
     let name = format!("4D packing found.");
     plot::plot_4d(&recipe, &dimension_tuples[0], &name);
     recipe.save_json(&String::from("tesseracts"), &name);
@@ -27,7 +37,6 @@ fn main() {
 fn backtrack_tesseracts(dimension_tuples: &Vec<DimensionTuple>) -> Recipe {
     let mut recipe_builder = RecipeBuilder::new(N, N, dimension_tuples.clone());
 
-    let max_tries = N * (N - 1) * (N - 2) * (N - 3);
     let coords = recipe_builder.get_recipe().map.coords().clone();
     println!("Coords: {:?}", coords.len());
 
@@ -37,8 +46,8 @@ fn backtrack_tesseracts(dimension_tuples: &Vec<DimensionTuple>) -> Recipe {
     //let mut successes: usize = 0;
     let now = Instant::now();
 
-    let indices: Vec<usize> = (0..N).collect();
-    let perms = combinatorics::permutations(&indices.as_slice(), N);
+    let perms = recipe_builder.orientations();
+    let max_tries = perms.len();
 
     loop {
         iteration += 1;
@@ -2,20 +2,20 @@ extern crate hoffman;
 
 use std::time::Instant;
 use hoffman::*;
+use hoffman::archive::*;
+use hoffman::sink::*;
 
 const N: usize = 4;
 const M: usize = 3;
 
 fn main() {
-    let dimension_tuples = vec!(
+    let dimension_tuples: Vec<DimensionTuple> = vec!(
         vec!(8, 9, 10, 12),  // Wide
         vec!(10, 12, 13, 14) // Narrow
-    );
+    ).into_iter().map(|sides| DimensionTuple::degenerate(sides).unwrap_or_else(|error| panic!("{}", error))).collect();
 
     println!("Dimension tuples: {:?}", dimension_tuples);
 
-    assert!(dimension_tuples.iter().all(|dimension_tuple| utils::list_has_unique_sums(&dimension_tuple)), "Dimension tuple does not have unique sums.");
-
     println!("Will determine packings.");
     let now = Instant::now();
     backtrack_cubes(&dimension_tuples);
@@ -24,19 +24,20 @@ fn main() {
 
 fn backtrack_cubes(dimension_tuples: &Vec<DimensionTuple>) {
     let mut recipe_builder = RecipeBuilder::new(N, M, dimension_tuples.clone());
+    let archive = RecipeArchive::create(&String::from("exports/cubes"), &String::from("cube-4d"))
+        .expect("Error opening archive.");
+    let mut solutions = ArchiveSink::new(archive, "cube-4d");
 
-    let max_tries = N * (N - 1) * (N - 2);
     let coords = recipe_builder.get_recipe().map.coords().clone();
     println!("Coords: {:?}", coords.len());
 
     let mut records = [[[0; N]; N]; N];
     let mut i: usize = 0;
     let mut iteration: usize = 0;
-    let mut successes: usize = 0;
     let now = Instant::now();
 
-    let indices: Vec<usize> = (0..N).collect();
-    let perms = combinatorics::permutations(&indices.as_slice(), M);
+    let perms = recipe_builder.orientations();
+    let max_tries = perms.len();
 
     loop {
         iteration += 1;
@@ -61,10 +62,8 @@ fn backtrack_cubes(dimension_tuples: &Vec<DimensionTuple>) {
 
             if recipe_builder.is_valid(coord) {
                 if i == N * N * N - 1 { // We have successfully placed all bricks.
-                    successes += 1;
-                    let name = format!("cube-4d-{}", successes);
-                    recipe_builder.get_recipe().save_json(&String::from("cubes"), &name);
-                    if successes >= 1000 { return }
+                    solutions.push(recipe_builder.get_recipe());
+                    if solutions.archive.len() >= 1000 { return }
                 } else {
                     i += 1; // Go to next coord.
                     continue;
@@ -1,6 +1,8 @@
 extern crate hoffman;
 
 use hoffman::*;
+use hoffman::archive::*;
+use hoffman::sink::*;
 use std::time::Instant;
 use std::iter::repeat;
 
@@ -8,15 +10,13 @@ const N: usize = 4;
 const M: usize = 3;
 
 fn main() {
-    let dimension_tuples = vec!(
+    let dimension_tuples: Vec<DimensionTuple> = vec!(
         vec!(8, 9, 10, 12),  // Wide
         vec!(10, 12, 13, 14) // Narrow
-    );
+    ).into_iter().map(|sides| DimensionTuple::degenerate(sides).unwrap_or_else(|error| panic!("{}", error))).collect();
 
     println!("Dimension tuples: {:?}", dimension_tuples);
 
-    assert!(dimension_tuples.iter().all(|dimension_tuple| utils::list_has_unique_sums(&dimension_tuple)), "Dimension tuple does not have unique sums.");
-
     println!("Will determine kernels.");
     let now = Instant::now();
     let unique_kernels = backtrack_kernels(&dimension_tuples);
@@ -25,10 +25,14 @@ fn main() {
 
     println!("Will determine number of unique cubes...");
     let now = Instant::now();
-    let unique_cube_count: usize = unique_kernels.iter().map(|kernel| {
-        backtrack_cubes(&dimension_tuples, &kernel)
+    let archive = RecipeArchive::create(&String::from("exports/cubes"), &String::from("kernel-cubes"))
+        .expect("Error opening archive.");
+    let mut cubes = (Dedupe::new(), ArchiveSink::new(archive, "cube"));
+    let cube_count: usize = unique_kernels.iter().map(|kernel| {
+        backtrack_cubes(&dimension_tuples, &kernel, &mut cubes)
     }).sum();
-    println!("Total unique cube count: {:?}", unique_cube_count);
+    println!("Total cube count: {:?}", cube_count);
+    println!("Total unique cube count: {:?}", cubes.1.archive.len());
     println!("Time spent making cubes: {:?} s", now.elapsed().as_secs());
 }
 
@@ -36,9 +40,6 @@ fn backtrack_kernels(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
 
     let mut kernels = Vec::new();
 
-    let indices: Vec<usize> = (0..N).collect();
-    let perms = combinatorics::permutations(&indices.as_slice(), M);
-
     let kernel_shape = repeat(M).take(M).collect();
     let coords: Vec<Coord> = utils::make_coords(&kernel_shape).iter().map(|coord| {
         coord.iter().map(|v| v + 1 ).collect::<Coord>()
@@ -48,6 +49,7 @@ fn backtrack_kernels(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
     let mut recipe_builder = RecipeBuilder::new(N, M, dimension_tuples.clone());
     let mut records = [[[0; N]; N]; N];
 
+    let perms = recipe_builder.orientations();
     let max_tries = perms.len();
 
     let mut i: usize = 0;
@@ -93,16 +95,13 @@ fn backtrack_kernels(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
     kernels
 }
 
-fn backtrack_cubes(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe) -> usize {
+fn backtrack_cubes<S: SolutionSink>(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe, cubes: &mut S) -> usize {
     let mut recipe_builder = RecipeBuilder::new(N, M, dimension_tuples.clone());
 
     let coords = recipe_builder.get_recipe().map.coords().clone();
     //println!("Coordinates: {:?}", coords);
 
-    let indices: Vec<usize> = (0..N).collect();
-    let perms: Vec<Orientation> = combinatorics::permutations(&indices.as_slice(), M);
-
-    let mut cubes = Vec::new();
+    let perms = recipe_builder.orientations();
 
     let mut records = [[[0; N]; N]; N];
 
@@ -120,7 +119,7 @@ fn backtrack_cubes(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe) -> u
         let coord = &coords[i];
         let (x, y, z) = (coord[0], coord[1], coord[2]);
         let inside_kernel = kernel.map.contains_key(coord);
-        let max_tries = if inside_kernel { 1 } else { N * (N - 1) * (N - 2) };
+        let max_tries = if inside_kernel { 1 } else { perms.len() };
 
         if records[x][y][z] < max_tries { // We'll try placing a brick.
             let next_perm = if inside_kernel { // Fetch next rotation and place brick.
@@ -136,8 +135,8 @@ fn backtrack_cubes(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe) -> u
             if recipe_builder.is_valid(coord) {
                 if i == N * N * N - 1 { // We have successfully placed all bricks.
                     successes += 1;
-                    let recipe = recipe_builder.get_recipe().clone();
-                    plot::plot_4d_cube(&recipe, &dimension_tuples[0], &format!("Cube at iteration {}", iteration));
+                    let recipe = recipe_builder.get_recipe();
+                    plot::plot_4d_cube(recipe, &dimension_tuples[0], &format!("Cube at iteration {}", iteration));
                     cubes.push(recipe);
                 } else {
                     i += 1; // Go to next coord.
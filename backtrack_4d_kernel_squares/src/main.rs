@@ -1,6 +1,8 @@
 extern crate hoffman;
 
 use hoffman::*;
+use hoffman::sink::*;
+use std::env;
 use std::time::Instant;
 use std::iter::repeat;
 
@@ -20,15 +22,20 @@ fn main() {
       Wide:      62 458 582 squares
       Universal: 51 247 458 squares */
 
-    let dimension_tuples = vec!(
-        vec!(8, 9, 10, 12),  // Wide
-        vec!(10, 12, 13, 14) // Narrow
-    );
+    // The case is given as `wide`, `narrow` or `equal`, and is universal
+    // otherwise. In the equal case `a1 + a4 = a2 + a3`.
+    let wide = vec!(8, 9, 10, 12);
+    let narrow = vec!(10, 12, 13, 14);
+    let equal = vec!(8, 9, 10, 11);
+    let dimension_tuples: Vec<DimensionTuple> = match env::args().nth(1).as_ref().map(|arg| arg.as_str()) {
+        Some("wide") => vec!(wide),
+        Some("narrow") => vec!(narrow),
+        Some("equal") => vec!(equal),
+        _ => vec!(wide, narrow)
+    }.into_iter().map(|sides| DimensionTuple::degenerate(sides).unwrap_or_else(|error| panic!("{}", error))).collect();
 
     println!("Dimension tuples: {:?}", dimension_tuples);
 
-    assert!(dimension_tuples.iter().all(|dimension_tuple| utils::list_has_unique_sums(&dimension_tuple)), "Brick does not have unique sums.");
-
     println!("Will determine kernels.");
     let now = Instant::now();
     let unique_kernels = backtrack_kernels(&dimension_tuples);
@@ -43,27 +50,30 @@ fn main() {
 
     println!("Will determine number of unique squares...");
     let now = Instant::now();
-    let mut unique_square_count: usize = 0;
+    let mut unique_squares = Count::new();
     let mut total_iterations: usize = 0;
     for (i, kernel) in unique_kernels.iter().enumerate() {
-        let (count, iterations) = backtrack_squares(&dimension_tuples, &kernel);
-        unique_square_count += count;
-        total_iterations += iterations;
+        total_iterations += if kernel.is_self_symmetric() {
+            let mut squares = Count::new();
+            let before_count = unique_squares.count;
+            let iterations = backtrack_squares(&dimension_tuples, &kernel, &mut (&mut squares, (Dedupe::new(), &mut unique_squares)));
+            println!("Symmetric kernel: Reduced squares from {:?} to {:?}", squares.count, unique_squares.count - before_count);
+            iterations
+        } else {
+            backtrack_squares(&dimension_tuples, &kernel, &mut unique_squares)
+        };
         if i % 10 == 0 {
             println!("{:.2}%", 100.0 * i as f64 / unique_kernels.len() as f64);
         }
     }
-    println!("Total unique square count: {:?}", unique_square_count);
+    println!("Total unique square count: {:?}", unique_squares.count);
     println!("Total iterations: {:?}", total_iterations);
     println!("Time spent making squares: {:?}", now.elapsed());
 }
 
 fn backtrack_kernels(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
 
-    let mut kernels = Vec::new();
-
-    let indices: Vec<usize> = (0..N).collect();
-    let perms = combinatorics::permutations(&indices.as_slice(), M);
+    let mut kernels = (Count::new(), (Dedupe::new(), Vec::new()));
 
     let kernel_shape = repeat(M).take(M).collect();
     let coords: Vec<Coord> = utils::make_coords(&kernel_shape).iter().map(|coord| {
@@ -74,6 +84,7 @@ fn backtrack_kernels(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
     let mut recipe_builder = RecipeBuilder::new(N, M, dimension_tuples.clone());
     let mut records = [[0; N]; N];
 
+    let perms = recipe_builder.orientations();
     let max_tries = perms.len();
 
     let mut i: usize = 0;
@@ -87,7 +98,7 @@ fn backtrack_kernels(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
             records[x][y] += 1; // Register that this rotation has been tried.
             if recipe_builder.satisfies_line_criterion(coord) {
                 if i == M * M - 1 { // We have successfully placed bricks everywhere.
-                    kernels.push(recipe_builder.get_recipe().clone());
+                    kernels.push(recipe_builder.get_recipe());
                 } else {
                     i += 1; // Go to next coord.
                     continue;
@@ -104,20 +115,18 @@ fn backtrack_kernels(dimension_tuples: &Vec<DimensionTuple>) -> Vec<Recipe> {
         recipe_builder.remove(&coords[i]);
     }
 
-    println!("Kernel count including rotations and reflections: {:?}", kernels.len());
-    Recipe::find_unique(kernels)
+    println!("Kernel count including rotations and reflections: {:?}", kernels.0.count);
+    (kernels.1).1
 }
 
-fn backtrack_squares(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe) -> (usize, usize) {
+/// Feeds the squares extending the kernel to the sink and returns the number of iterations.
+fn backtrack_squares<S: SolutionSink>(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe, squares: &mut S) -> usize {
     let mut recipe_builder = RecipeBuilder::new(N, M, dimension_tuples.clone());
 
     let coords = recipe_builder.get_recipe().map.coords().clone();
     //println!("Coordinates: {:?}", coords);
 
-    let indices: Vec<usize> = (0..N).collect();
-    let perms: Vec<Orientation> = combinatorics::permutations(&indices.as_slice(), M);
-
-    let mut squares = Vec::new();
+    let perms = recipe_builder.orientations();
 
     let mut records = [[0; N]; N];
 
@@ -130,7 +139,7 @@ fn backtrack_squares(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe) ->
         let coord = &coords[i];
         let (x, y) = (coord[0], coord[1]);
         let inside_kernel = kernel.map.contains_key(coord);
-        let max_tries = if inside_kernel { 1 } else { N * (N - 1) };
+        let max_tries = if inside_kernel { 1 } else { perms.len() };
 
         if records[x][y] < max_tries { // We'll try placing a brick.
             let next_perm = if inside_kernel { // Fetch next rotation and place brick.
@@ -145,7 +154,7 @@ fn backtrack_squares(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe) ->
 
             if recipe_builder.is_valid(coord) {
                 if i == N * N - 1 { // We have successfully placed all bricks.
-                    squares.push(recipe_builder.get_recipe().clone());
+                    squares.push(recipe_builder.get_recipe());
                 } else {
                     i += 1; // Go to next coord.
                     continue;
@@ -161,17 +170,7 @@ fn backtrack_squares(dimension_tuples: &Vec<DimensionTuple>, kernel: &Recipe) ->
         }
         recipe_builder.remove(&coords[i]);
     }
-    let before_count = squares.len();
-    let unique_squares = if kernel.is_self_symmetric() {
-        let now = Instant::now();
-        let unique = Recipe::find_unique(squares);
-        println!("Symmetric kernel: Reduced squares from {:?} to {:?}", before_count, unique.len());
-        println!("Time spent removing symmetries: {:?}", now.elapsed());
-        unique
-    } else {
-        squares
-    };
-    (unique_squares.len(), iteration)
+    iteration
 }
 
 pub fn kernel_plot(kernel: &Recipe, dimension_tuple: &DimensionTuple, name: &String) {
@@ -183,7 +182,7 @@ pub fn kernel_plot(kernel: &Recipe, dimension_tuple: &DimensionTuple, name: &Str
         for x in 1..=2 {
             for y in 1..=2 {
                 let orientations = kernel.map.get(&vec!(x, y)).unwrap();
-                let size: DimensionTuple = orientations.iter().map(|&i| dimension_tuple[i]).collect();
+                let size: Vec<IntType> = orientations.iter().map(|&i| dimension_tuple[i]).collect();
                 let rectangle = plot::Rectangle {
                     x: center + if x == 1 { -size[0] } else { 0 },
                     y: center + if y == 1 { -size[1] } else { 0 },
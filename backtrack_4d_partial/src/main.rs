@@ -6,15 +6,13 @@ use hoffman::*;
 const N: usize = 4;
 
 fn main() {
-    let dimension_tuples = vec!(
+    let dimension_tuples: Vec<DimensionTuple> = vec!(
         vec!(8, 9, 10, 12),  // Wide
         vec!(10, 12, 13, 14) // Narrow
-    );
+    ).into_iter().map(|sides| DimensionTuple::degenerate(sides).unwrap_or_else(|error| panic!("{}", error))).collect();
 
     println!("Dimension tuples: {:?}", dimension_tuples);
 
-    assert!(dimension_tuples.iter().all(|dimension_tuple| utils::list_has_unique_sums(&dimension_tuple)), "Dimension tuple does not have unique sums.");
-
     println!("Will determine packings.");
     let now = Instant::now();
     backtrack_tesseracts(&dimension_tuples);
@@ -25,7 +23,8 @@ fn main() {
 fn backtrack_tesseracts(dimension_tuples: &Vec<DimensionTuple>) {
     let mut recipe_builder = RecipeBuilder::new(N, N, dimension_tuples.clone());
 
-    let max_tries = N * (N - 1) * (N - 2) * (N - 3);
+    let perms = recipe_builder.orientations();
+    let max_tries = perms.len();
     let coords = recipe_builder.get_recipe().map.coords().clone();
 
     println!("Coords: {:?}", coords.len());
@@ -47,9 +46,6 @@ fn backtrack_tesseracts(dimension_tuples: &Vec<DimensionTuple>) {
     }
     println!("Configured.");
 
-    let indices: Vec<usize> = (0..N).collect();
-    let perms = combinatorics::permutations(&indices.as_slice(), N);
-
     loop {
         iteration += 1;
         if iteration % 10_000_000 == 0 {
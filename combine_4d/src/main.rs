@@ -107,8 +107,8 @@ impl Group {
 fn main() {
     let now = Instant::now();
 
-    let dimension_tuple = vec!(8, 9, 10, 12); // Wide
-    //let dimension_tuple = vec!(10, 12, 13, 14); // Narrow
+    let dimension_tuple = DimensionTuple::degenerate(vec!(8, 9, 10, 12)).unwrap(); // Wide
+    //let dimension_tuple = DimensionTuple::degenerate(vec!(10, 12, 13, 14)).unwrap(); // Narrow
 
     let mut map = NdArray::new(&vec!(2, 2));
     map.insert(&vec!(0, 0), vec!(0, 1));
@@ -128,8 +128,7 @@ fn main() {
     let solution_options = combinatorics::product(&repeat(solutions).take(2).collect::<Vec<_>>());
     println!("Solution options: {:?}", solution_options.len());
 
-    let indices: Vec<usize> = (0..N).collect();
-    let perms = combinatorics::permutations(&indices.as_slice(), N);
+    let perms = RecipeBuilder::new(N, N, vec!(dimension_tuple.clone())).orientations();
 
     let mut recipes: Vec<Recipe> = Vec::new();
 
@@ -150,6 +149,14 @@ fn main() {
     make_statistics(&recipes);
     check_duality(&recipes, &dimension_tuple);
 
+    let signature_table = signatures::SignatureTable::enumerate(N, 60);
+    let validity_matrix = validity::ValidityMatrix::new(&recipes, &signature_table);
+    let name = String::from("4D Combined Packings validity");
+    validity_matrix.save_csv(&String::from("tesseracts"), &name);
+    validity_matrix.save_json(&String::from("tesseracts"), &name);
+    println!("Universal recipes up to symmetry: {} of {}",
+        validity_matrix.rows.iter().filter(|row| row.universal).count(), validity_matrix.rows.len());
+
     println!("Time spent making recipe: {:?}", now.elapsed());
 }
 
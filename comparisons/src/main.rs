@@ -70,6 +70,14 @@ fn main() {
         println!("Unknowns: {}", count_unknown_comparisions(&comparison_table));
         print_comparison_table(&comparison_table);
 
+        let comparisons = signatures::undecided_comparisons(n);
+        let patterns = signatures::realisable_patterns(n, false);
+        println!("Realisable patterns of {} disjoint unknowns: {}", comparisons.len(), patterns.len());
+        for (pattern, witness) in patterns.iter().take(12) {
+            println!("{}: {:?}", signatures::signature_str(pattern), witness);
+        }
+        println!("");
+
     }
 
 }
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions, create_dir_all, read_dir};
+use std::io::{BufRead, BufReader, Error, ErrorKind, Result, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use serde_json::Value;
+
+use super::*;
+
+/// A recipe stored in an archive under a name.
+#[derive(Clone)]
+pub struct ArchiveEntry {
+    pub name: String,
+    /// The `Recipe::canonical_hash` of the recipe.
+    pub hash: String,
+    pub recipe: Recipe
+}
+
+impl ArchiveEntry {
+    fn to_line(&self) -> String {
+        format!("{{ \"name\": {}, \"hash\": \"{}\", \"recipe\": {} }}\n",
+            Value::String(self.name.clone()), self.hash, self.recipe.to_json().replace('\n', " "))
+    }
+
+    fn from_line(line: &str) -> Result<ArchiveEntry> {
+        let value: Value = serde_json::from_str(line).map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+        match (value["name"].as_str(), value["hash"].as_str(), value["recipe"].is_object()) {
+            (Some(name), Some(hash), true) => Ok(ArchiveEntry {
+                name: name.to_string(),
+                hash: hash.to_string(),
+                recipe: Recipe::from_json(&value["recipe"].to_string())
+            }),
+            _ => Err(Error::new(ErrorKind::InvalidData, format!("Not an archive entry: {}", line)))
+        }
+    }
+}
+
+/// Many recipes in a single JSON Lines file, one entry per line.
+///
+/// Only the byte offsets of the entries and an index from canonical hashes to
+/// entries are held in memory; recipes are read from the file when needed.
+pub struct RecipeArchive {
+    path: PathBuf,
+    offsets: Vec<u64>,
+    index: HashMap<String, Vec<usize>>
+}
+
+impl RecipeArchive {
+    /// Opens the archive at `directory/file_name.jsonl`, which must exist.
+    pub fn open(directory: &String, file_name: &String) -> Result<RecipeArchive> {
+        let path = Path::new(directory).join(format!("{}.jsonl", file_name));
+        let mut archive = RecipeArchive { path, offsets: Vec::new(), index: HashMap::new() };
+        let mut reader = BufReader::new(File::open(&archive.path)?);
+        let mut offset = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)? as u64;
+            if read == 0 { break }
+            if !line.trim().is_empty() {
+                let entry = ArchiveEntry::from_line(&line)?;
+                archive.register(offset, entry.hash);
+            }
+            offset += read;
+        }
+        Ok(archive)
+    }
+
+    /// Opens `directory/file_name.jsonl`, creating an empty archive if there is none.
+    pub fn open_or_create(directory: &String, file_name: &String) -> Result<RecipeArchive> {
+        create_dir_all(directory)?;
+        OpenOptions::new().create(true).append(true).open(Path::new(directory).join(format!("{}.jsonl", file_name)))?;
+        RecipeArchive::open(directory, file_name)
+    }
+
+    /// Creates an empty archive at `directory/file_name.jsonl`, replacing an existing one.
+    pub fn create(directory: &String, file_name: &String) -> Result<RecipeArchive> {
+        create_dir_all(directory)?;
+        let path = Path::new(directory).join(format!("{}.jsonl", file_name));
+        File::create(&path)?;
+        Ok(RecipeArchive { path, offsets: Vec::new(), index: HashMap::new() })
+    }
+
+    fn register(&mut self, offset: u64, hash: String) {
+        self.index.entry(hash).or_default().push(self.offsets.len());
+        self.offsets.push(offset);
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Appends the recipe, even if an equivalent one is stored already.
+    pub fn append(&mut self, name: &str, recipe: &Recipe) -> Result<()> {
+        let entry = ArchiveEntry { name: name.to_string(), hash: recipe.canonical_hash(), recipe: recipe.clone() };
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        let offset = file.seek(SeekFrom::End(0))?;
+        file.write_all(entry.to_line().as_bytes())?;
+        self.register(offset, entry.hash);
+        Ok(())
+    }
+
+    /// Appends the recipe unless a symmetry of it is stored already.
+    /// Returns whether it was appended.
+    pub fn insert(&mut self, name: &str, recipe: &Recipe) -> Result<bool> {
+        if self.lookup(recipe)?.is_some() {
+            return Ok(false)
+        }
+        self.append(name, recipe)?;
+        Ok(true)
+    }
+
+    pub fn get(&self, index: usize) -> Result<Option<ArchiveEntry>> {
+        let offset = match self.offsets.get(index) {
+            Some(&offset) => offset,
+            None => return Ok(None)
+        };
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        ArchiveEntry::from_line(&line).map(Some)
+    }
+
+    /// The entries whose recipes have the canonical hash.
+    pub fn find(&self, hash: &str) -> Result<Vec<ArchiveEntry>> {
+        let indices = self.index.get(hash).cloned().unwrap_or_default();
+        indices.into_iter().map(|index| self.get(index).map(|entry| entry.unwrap())).collect()
+    }
+
+    /// The stored entry whose recipe is a symmetry of the given one.
+    pub fn lookup(&self, recipe: &Recipe) -> Result<Option<ArchiveEntry>> {
+        let canonical = recipe.canonical();
+        Ok(self.find(&recipe.canonical_hash())?.into_iter().find(|entry| entry.recipe.canonical() == canonical))
+    }
+
+    /// Reads the entries in the order they were appended.
+    pub fn iter(&self) -> Result<impl Iterator<Item = Result<ArchiveEntry>>> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        Ok(reader.lines().filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+            .map(|line| ArchiveEntry::from_line(&line?)))
+    }
+
+    pub fn recipes(&self) -> Result<Vec<Recipe>> {
+        self.iter()?.map(|entry| entry.map(|entry| entry.recipe)).collect()
+    }
+
+    /// Inserts every recipe stored as a JSON file in the directory, named by
+    /// its file stem, in natural order of the names so that `cube-4d-2`
+    /// precedes `cube-4d-10`. Other JSON files, such as box lists, are skipped.
+    /// Returns the number of recipes inserted.
+    pub fn import_directory(&mut self, directory: &String) -> Result<usize> {
+        let mut names = Vec::new();
+        for file in read_dir(directory)? {
+            let path = file?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                names.push(path.file_stem().unwrap().to_string_lossy().to_string());
+            }
+        }
+        names.sort_by_key(|name| natural_key(name));
+        let mut inserted = 0;
+        for name in &names {
+            let json = utils::read_file(directory, &format!("{}.json", name))?;
+            let is_recipe = serde_json::from_str::<Value>(&json).ok()
+                .is_some_and(|value| value["n"].is_u64() && value["m"].is_u64() && value["map"].is_array());
+            if is_recipe && self.insert(name, &Recipe::from_json(&json))? {
+                inserted += 1;
+            }
+        }
+        Ok(inserted)
+    }
+}
+
+/// Splits a name into text and numbers, so that numbers compare by value.
+fn natural_key(name: &str) -> Vec<(String, u64)> {
+    let mut key = Vec::new();
+    let mut chars = name.chars().peekable();
+    while chars.peek().is_some() {
+        let text: String = std::iter::from_fn(|| chars.next_if(|c| !c.is_ascii_digit())).collect();
+        let digits: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit())).collect();
+        key.push((text, digits.parse().unwrap_or(0)));
+    }
+    key
+}
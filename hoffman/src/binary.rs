@@ -0,0 +1,254 @@
+use std::fs::{File, OpenOptions, create_dir_all};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::convert::TryFrom;
+use std::path::Path;
+use memmap2::Mmap;
+
+use super::*;
+
+const MAGIC: &[u8; 8] = b"HOFFMAN1";
+
+/// The layout shared by the header and the records of a binary recipe file.
+///
+/// A file starts with `HOFFMAN1`, then `n`, `m` and the number of tuples as
+/// little-endian `u32`, then the sides of every tuple as little-endian `i64`.
+/// After the header come the recipes as records of equal size. Each cell, in
+/// the order of `NdArray::coords`, holds the rank of its orientation among the
+/// arrangements of `m` of the `n` sides, or the one rank past them for an
+/// empty cell, in `bits` bits, least significant first. Records are padded to
+/// whole bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinaryHeader {
+    pub n: usize,
+    pub m: usize,
+    pub dimension_tuples: Vec<Vec<IntType>>
+}
+
+impl BinaryHeader {
+    pub fn new(n: usize, m: usize, dimension_tuples: Vec<Vec<IntType>>) -> BinaryHeader {
+        assert!(dimension_tuples.iter().all(|sides| sides.len() == n), "Dimension tuple does not have n sides.");
+        BinaryHeader { n, m, dimension_tuples }
+    }
+
+    fn arrangements(&self) -> u64 {
+        (0..self.m).map(|i| (self.n - i) as u64).product()
+    }
+
+    /// Bits per cell, enough for every arrangement and the empty cell.
+    /// This is `⌈log2 n!⌉` when `m = n > 2`.
+    pub fn bits(&self) -> usize {
+        (64 - self.arrangements().leading_zeros()) as usize
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.n.pow(self.m as u32)
+    }
+
+    pub fn record_size(&self) -> usize {
+        (self.cell_count() * self.bits()).div_ceil(8)
+    }
+
+    /// Size of the header in bytes.
+    pub fn size(&self) -> usize {
+        MAGIC.len() + 12 + 8 * self.n * self.dimension_tuples.len()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        for value in &[self.n, self.m, self.dimension_tuples.len()] {
+            bytes.extend_from_slice(&(*value as u32).to_le_bytes());
+        }
+        for &side in self.dimension_tuples.iter().flatten() {
+            bytes.extend_from_slice(&(side as i64).to_le_bytes());
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<BinaryHeader> {
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+        if bytes.len() < MAGIC.len() + 12 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(invalid("Not a binary recipe file."))
+        }
+        let word = |i: usize| {
+            let offset = MAGIC.len() + 4 * i;
+            u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]) as usize
+        };
+        let (n, m, count) = (word(0), word(1), word(2));
+        if n == 0 || m == 0 || m > n {
+            return Err(invalid("Binary recipe header needs 0 < m <= n."))
+        }
+        // The ranks, the cells of a record and the sides must all be countable.
+        let arrangements = (0..m).try_fold(1u64, |product, i| product.checked_mul((n - i) as u64));
+        let record_bits = arrangements.and_then(|arrangements| {
+            n.checked_pow(m as u32)?.checked_mul((64 - arrangements.leading_zeros()) as usize)
+        });
+        let begin = MAGIC.len() + 12;
+        let end = n.checked_mul(count).and_then(|sides| sides.checked_mul(8)).and_then(|size| size.checked_add(begin));
+        let end = match (record_bits, end) {
+            (Some(_), Some(end)) => end,
+            _ => return Err(invalid("Binary recipe header has sizes too large."))
+        };
+        if bytes.len() < end {
+            return Err(invalid("Binary recipe header is truncated."))
+        }
+        let dimension_tuples = (0..count).map(|t| {
+            (0..n).map(|i| {
+                let offset = begin + 8 * (t * n + i);
+                let mut side = [0; 8];
+                side.copy_from_slice(&bytes[offset..offset + 8]);
+                IntType::try_from(i64::from_le_bytes(side)).map_err(|_| invalid("Binary recipe side is out of range."))
+            }).collect()
+        }).collect::<Result<_>>()?;
+        Ok(BinaryHeader { n, m, dimension_tuples })
+    }
+
+    pub fn encode(&self, recipe: &Recipe) -> Vec<u8> {
+        assert!(recipe.n == self.n && recipe.m == self.m, "Recipe does not match the header.");
+        let bits = self.bits();
+        let mut record = vec!(0u8; self.record_size());
+        for (cell, coord) in recipe.map.coords().iter().enumerate() {
+            let rank = match recipe.map.get(coord) {
+                Some(orientation) => orientation_rank(orientation, self.n),
+                None => self.arrangements()
+            };
+            for bit in 0..bits {
+                if rank >> bit & 1 == 1 {
+                    let position = cell * bits + bit;
+                    record[position / 8] |= 1 << (position % 8);
+                }
+            }
+        }
+        record
+    }
+
+    pub fn decode(&self, record: &[u8]) -> Recipe {
+        let bits = self.bits();
+        let mut recipe = Recipe::new(self.n, self.m);
+        for (cell, coord) in recipe.map.coords().clone().iter().enumerate() {
+            let rank = (0..bits).fold(0u64, |rank, bit| {
+                let position = cell * bits + bit;
+                rank | ((record[position / 8] >> (position % 8) & 1) as u64) << bit
+            });
+            if rank < self.arrangements() {
+                recipe.map.insert(coord, orientation_unrank(rank, self.n, self.m));
+            }
+        }
+        recipe
+    }
+}
+
+/// The rank of an arrangement of `orientation.len()` of the sides `0..n` in
+/// lexicographic order, that is its Lehmer code read as a mixed-radix number.
+pub fn orientation_rank(orientation: &[usize], n: usize) -> u64 {
+    let m = orientation.len();
+    let mut unused: Vec<usize> = (0..n).collect();
+    let mut rank = 0;
+    for (i, side) in orientation.iter().enumerate() {
+        let position = unused.iter().position(|s| s == side).expect("Orientation repeats a side.");
+        unused.remove(position);
+        let remaining: u64 = (i + 1..m).map(|j| (n - j) as u64).product();
+        rank += position as u64 * remaining;
+    }
+    rank
+}
+
+pub fn orientation_unrank(mut rank: u64, n: usize, m: usize) -> Orientation {
+    let mut unused: Vec<usize> = (0..n).collect();
+    (0..m).map(|i| {
+        let remaining: u64 = (i + 1..m).map(|j| (n - j) as u64).product();
+        let position = (rank / remaining) as usize;
+        rank %= remaining;
+        unused.remove(position)
+    }).collect()
+}
+
+/// Appends recipes to a binary recipe file.
+pub struct BinaryWriter {
+    header: BinaryHeader,
+    file: File
+}
+
+impl BinaryWriter {
+    /// Creates the file, replacing an existing one.
+    pub fn create(directory: &String, file_name: &String, header: BinaryHeader) -> Result<BinaryWriter> {
+        create_dir_all(directory)?;
+        let mut file = File::create(Path::new(&directory).join(format!("{}.bin", file_name)))?;
+        file.write_all(&header.to_bytes())?;
+        Ok(BinaryWriter { header, file })
+    }
+
+    /// Opens an existing file to append to it.
+    pub fn append(directory: &String, file_name: &String) -> Result<BinaryWriter> {
+        let path = Path::new(directory).join(format!("{}.bin", file_name));
+        let mut bytes = Vec::new();
+        File::open(&path)?.read_to_end(&mut bytes)?;
+        let header = BinaryHeader::from_bytes(&bytes)?;
+        if (bytes.len() - header.size()) % header.record_size() != 0 {
+            return Err(Error::new(ErrorKind::InvalidData, "Binary recipe file ends inside a record."))
+        }
+        let file = OpenOptions::new().append(true).open(&path)?;
+        Ok(BinaryWriter { header, file })
+    }
+
+    pub fn header(&self) -> &BinaryHeader {
+        &self.header
+    }
+
+    pub fn push(&mut self, recipe: &Recipe) -> Result<()> {
+        self.file.write_all(&self.header.encode(recipe))
+    }
+
+    /// Appends the recipes stored as JSON files, reading one at a time.
+    pub fn push_json_files(&mut self, directory: &String, file_names: &[String]) -> Result<()> {
+        for file_name in file_names {
+            let json = utils::read_file(directory, &format!("{}.json", file_name))?;
+            self.push(&Recipe::from_json(&json))?;
+        }
+        Ok(())
+    }
+}
+
+/// A memory-mapped binary recipe file, read a recipe at a time.
+pub struct BinaryArchive {
+    header: BinaryHeader,
+    map: Mmap
+}
+
+impl BinaryArchive {
+    pub fn open(directory: &String, file_name: &String) -> Result<BinaryArchive> {
+        let file = File::open(Path::new(directory).join(format!("{}.bin", file_name)))?;
+        // The file is only read, and records written after mapping are simply not seen.
+        let map = unsafe { Mmap::map(&file)? };
+        let header = BinaryHeader::from_bytes(&map)?;
+        Ok(BinaryArchive { header, map })
+    }
+
+    pub fn header(&self) -> &BinaryHeader {
+        &self.header
+    }
+
+    pub fn len(&self) -> usize {
+        (self.map.len() - self.header.size()) / self.header.record_size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<Recipe> {
+        if index >= self.len() { return None }
+        let begin = self.header.size() + index * self.header.record_size();
+        Some(self.header.decode(&self.map[begin..begin + self.header.record_size()]))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Recipe> + '_ {
+        (0..self.len()).map(move |index| self.get(index).unwrap())
+    }
+
+    /// Writes every recipe as `"{prefix} {index}"` in the JSON format of `Recipe::save_json`.
+    pub fn save_json(&self, directory: &String, prefix: &String) {
+        for (index, recipe) in self.iter().enumerate() {
+            recipe.save_json(directory, &format!("{} {}", prefix, index));
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use serde_json::Value;
+
+use super::*;
+
+/// A brick of a packing with its absolute position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlacedBox {
+    /// Grid cell of the brick, empty when imported without one.
+    pub coord: Coord,
+    /// Orientation of the brick, empty when imported without one.
+    pub orientation: Orientation,
+    pub hyper_rectangle: HyperRectangle
+}
+
+/// The bricks of a packing for one dimension tuple.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoxList {
+    pub dimension_tuple: Vec<IntType>,
+    pub boxes: Vec<PlacedBox>
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BoxListError {
+    /// The file could not be read.
+    Read(String),
+    Parse(String),
+    /// The number of boxes is not `n^m` for the `n` sides of the tuple.
+    Count(usize),
+    /// No box starts where the brick at the coordinate should.
+    Missing(Coord),
+    /// Several boxes start where the brick at the coordinate should.
+    Ambiguous(Coord),
+    /// The box at the coordinate has a width that is not an unused side of the tuple.
+    Width(Coord, IntType),
+    /// The box at the index has another number of axes than the first box.
+    Dimension(usize),
+    /// The box starting where the brick at the coordinate should already fills another cell.
+    Reused(Coord),
+    /// The packing generated by the inferred recipe places the brick at the coordinate elsewhere.
+    Mismatch(Coord)
+}
+
+impl fmt::Display for BoxListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoxListError::Read(error) => write!(f, "Could not read box list: {}", error),
+            BoxListError::Parse(line) => write!(f, "Could not parse: {}", line),
+            BoxListError::Count(count) => write!(f, "{} boxes do not make a grid.", count),
+            BoxListError::Missing(coord) => write!(f, "No box starts where the brick at {:?} should.", coord),
+            BoxListError::Ambiguous(coord) => write!(f, "Several boxes start where the brick at {:?} should.", coord),
+            BoxListError::Width(coord, width) => write!(f, "Box at {:?} has width {}, which is not a side left to use.", coord, width),
+            BoxListError::Dimension(i) => write!(f, "Box {} does not have as many axes as the first box.", i),
+            BoxListError::Reused(coord) => write!(f, "The box where the brick at {:?} should start already fills another cell.", coord),
+            BoxListError::Mismatch(coord) => write!(f, "The inferred recipe places the brick at {:?} elsewhere.", coord)
+        }
+    }
+}
+
+fn list<T: ToString>(values: &[T], separator: &str) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(separator)
+}
+
+impl BoxList {
+    pub fn new(recipe_builder: &RecipeBuilder, dimension_tuple: &[IntType], index: usize) -> BoxList {
+        let recipe = recipe_builder.get_recipe();
+        let boxes = recipe_builder.get_packing(index).iter().map(|(coord, hyper_rectangle)| {
+            PlacedBox {
+                coord: coord.clone(),
+                orientation: recipe.map.get(coord).cloned().unwrap_or_default(),
+                hyper_rectangle: hyper_rectangle.clone()
+            }
+        }).collect();
+        BoxList { dimension_tuple: dimension_tuple.to_vec(), boxes }
+    }
+
+    pub fn to_json(&self) -> String {
+        let boxes: Vec<String> = self.boxes.iter().map(|placed| {
+            let begin: Vec<IntType> = placed.hyper_rectangle.iter().map(|interval| interval.begin).collect();
+            let end: Vec<IntType> = placed.hyper_rectangle.iter().map(|interval| interval.end).collect();
+            format!("{{ \"coord\": {:?}, \"orientation\": {:?}, \"begin\": {:?}, \"end\": {:?} }}",
+                placed.coord, placed.orientation, begin, end)
+        }).collect();
+        format!("{{ \"dimension_tuple\": {:?}, \"boxes\": [\n{}\n] }}", self.dimension_tuple, boxes.join(",\n"))
+    }
+
+    /// One row per box with columns `coord1, .., orientation1, .., begin1, end1, ..`.
+    pub fn to_csv(&self) -> String {
+        let m = self.boxes.first().map(|placed| placed.hyper_rectangle.len()).unwrap_or(0);
+        let mut columns = Vec::new();
+        for prefix in &["coord", "orientation"] {
+            columns.extend((1..=m).map(|d| format!("{}{}", prefix, d)));
+        }
+        for d in 1..=m {
+            columns.push(format!("begin{}", d));
+            columns.push(format!("end{}", d));
+        }
+        let mut csv = format!("{}\n", columns.join(","));
+        for placed in &self.boxes {
+            let bounds: Vec<String> = placed.hyper_rectangle.iter().map(|interval| format!("{},{}", interval.begin, interval.end)).collect();
+            csv.push_str(&format!("{},{},{}\n", list(&placed.coord, ","), list(&placed.orientation, ","), bounds.join(",")));
+        }
+        csv
+    }
+
+    pub fn save_json(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_json(), &format!("exports/{}", directory), &format!("{}.json", file_name))
+          .expect("Error writing box list to file");
+    }
+
+    pub fn save_csv(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_csv(), &format!("exports/{}", directory), &format!("{}.csv", file_name))
+          .expect("Error writing box list to file");
+    }
+
+    pub fn from_json(json: &str) -> Result<BoxList, BoxListError> {
+        let error = || BoxListError::Parse(String::from("box list json"));
+        let value: Value = serde_json::from_str(json).map_err(|e| BoxListError::Parse(e.to_string()))?;
+        let numbers = |value: &Value| -> Option<Vec<i64>> {
+            value.as_array()?.iter().map(|v| v.as_i64()).collect()
+        };
+        let sides = |value: &Value| -> Option<Vec<IntType>> {
+            numbers(value)?.into_iter().map(|v| IntType::try_from(v).ok()).collect()
+        };
+        let dimension_tuple = sides(&value["dimension_tuple"]).ok_or_else(error)?;
+        let boxes = value["boxes"].as_array().ok_or_else(error)?.iter().map(|placed| {
+            let indices = |key: &str| -> Option<Vec<usize>> {
+                if placed[key].is_null() { return Some(Vec::new()) }
+                numbers(&placed[key])?.into_iter().map(|v| usize::try_from(v).ok()).collect()
+            };
+            let begin = sides(&placed["begin"]).ok_or_else(error)?;
+            let end = sides(&placed["end"]).ok_or_else(error)?;
+            if begin.len() != end.len() {
+                return Err(BoxListError::Parse(placed.to_string()))
+            }
+            Ok(PlacedBox {
+                coord: indices("coord").ok_or_else(error)?,
+                orientation: indices("orientation").ok_or_else(error)?,
+                hyper_rectangle: begin.iter().zip(end.iter()).map(|(&begin, &end)| Interval { begin, end }).collect()
+            })
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(BoxList { dimension_tuple, boxes })
+    }
+
+    /// Reads the columns `begin1, end1, ..` and, if present, `coord1, ..` and
+    /// `orientation1, ..`, in any order. Other columns are ignored.
+    pub fn from_csv(csv: &str, dimension_tuple: &[IntType]) -> Result<BoxList, BoxListError> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header: Vec<&str> = lines.next().ok_or_else(|| BoxListError::Parse(String::from("missing header")))?
+            .split(',').map(|column| column.trim()).collect();
+        let columns = |prefix: &str| -> Vec<usize> {
+            (1..).map(|d| header.iter().position(|&column| column == format!("{}{}", prefix, d)))
+                .take_while(|position| position.is_some()).map(|position| position.unwrap()).collect()
+        };
+        let (coords, orientations, begins, ends) = (columns("coord"), columns("orientation"), columns("begin"), columns("end"));
+        if begins.is_empty() || begins.len() != ends.len() {
+            return Err(BoxListError::Parse(header.join(",")))
+        }
+        let boxes = lines.map(|line| {
+            let values: Vec<i64> = line.split(',').map(|value| value.trim().parse())
+                .collect::<Result<_, _>>().map_err(|_| BoxListError::Parse(line.to_string()))?;
+            let error = || BoxListError::Parse(line.to_string());
+            let index = |i: usize| values.get(i).and_then(|&v| usize::try_from(v).ok()).ok_or_else(error);
+            let bound = |i: usize| values.get(i).and_then(|&v| IntType::try_from(v).ok()).ok_or_else(error);
+            Ok(PlacedBox {
+                coord: coords.iter().map(|&i| index(i)).collect::<Result<_, _>>()?,
+                orientation: orientations.iter().map(|&i| index(i)).collect::<Result<_, _>>()?,
+                hyper_rectangle: begins.iter().zip(ends.iter()).map(|(&b, &e)| {
+                    Ok(Interval { begin: bound(b)?, end: bound(e)? })
+                }).collect::<Result<_, _>>()?
+            })
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(BoxList { dimension_tuple: dimension_tuple.to_vec(), boxes })
+    }
+
+    pub fn load_json(directory: &String, file_name: &String) -> Result<BoxList, BoxListError> {
+        let json = utils::read_file(directory, &format!("{}.json", file_name))
+          .map_err(|e| BoxListError::Read(e.to_string()))?;
+        BoxList::from_json(&json)
+    }
+
+    pub fn load_csv(directory: &String, file_name: &String, dimension_tuple: &[IntType]) -> Result<BoxList, BoxListError> {
+        let csv = utils::read_file(directory, &format!("{}.csv", file_name))
+          .map_err(|e| BoxListError::Read(e.to_string()))?;
+        BoxList::from_csv(&csv, dimension_tuple)
+    }
+
+    /// The recipe whose packing places the bricks at the positions of the boxes.
+    ///
+    /// Only positions and widths are used, after moving the least corner to
+    /// the origin. The cells are visited in order, and each must be filled by
+    /// the one box starting at the ends of its predecessors along every axis,
+    /// whose widths are sides of the tuple not yet used by the brick. Every box
+    /// must fill exactly one cell, and the packing generated by the recipe
+    /// must place each brick where its box is.
+    pub fn infer_recipe(&self) -> Result<Recipe, BoxListError> {
+        let n = self.dimension_tuple.len();
+        let m = self.boxes.first().map(|placed| placed.hyper_rectangle.len()).unwrap_or(0);
+        if m == 0 || n.checked_pow(m as u32) != Some(self.boxes.len()) {
+            return Err(BoxListError::Count(self.boxes.len()))
+        }
+        if let Some(i) = self.boxes.iter().position(|placed| placed.hyper_rectangle.len() != m) {
+            return Err(BoxListError::Dimension(i))
+        }
+        let origin: Vec<IntType> = (0..m).map(|d| {
+            self.boxes.iter().map(|placed| placed.hyper_rectangle[d].begin).min().unwrap()
+        }).collect();
+        let mut starts: HashMap<Vec<IntType>, Vec<usize>> = HashMap::new();
+        for (i, placed) in self.boxes.iter().enumerate() {
+            let begin = placed.hyper_rectangle.iter().zip(origin.iter()).map(|(interval, o)| interval.begin - o).collect();
+            starts.entry(begin).or_default().push(i);
+        }
+
+        let mut recipe = Recipe::new(n, m);
+        let mut ends: NdArray<Vec<IntType>> = NdArray::new(&vec!(n; m));
+        let mut filled: NdArray<usize> = NdArray::new(&vec!(n; m));
+        let mut used_boxes = vec!(false; self.boxes.len());
+        for coord in recipe.map.coords().clone() {
+            let begin: Vec<IntType> = (0..m).map(|d| {
+                if coord[d] == 0 { return 0 }
+                let mut previous = coord.clone();
+                previous[d] -= 1;
+                ends.get(&previous).unwrap()[d]
+            }).collect();
+            let i = match starts.get(&begin).map(|found| found.as_slice()) {
+                Some(&[i]) => i,
+                Some(_) => return Err(BoxListError::Ambiguous(coord)),
+                None => return Err(BoxListError::Missing(coord))
+            };
+            if used_boxes[i] {
+                return Err(BoxListError::Reused(coord))
+            }
+            used_boxes[i] = true;
+            filled.insert(&coord, i);
+            let placed = &self.boxes[i];
+            let mut used = vec!(false; n);
+            let mut orientation = Vec::with_capacity(m);
+            for interval in &placed.hyper_rectangle {
+                let width = interval.width();
+                let side = (0..n).find(|&i| !used[i] && self.dimension_tuple[i] == width)
+                    .ok_or_else(|| BoxListError::Width(coord.clone(), width))?;
+                used[side] = true;
+                orientation.push(side);
+            }
+            ends.insert(&coord, begin.iter().zip(orientation.iter()).map(|(b, &side)| b + self.dimension_tuple[side]).collect());
+            recipe.map.insert(&coord, orientation);
+        }
+
+        // As many cells as boxes were filled by distinct boxes, so every box is used once.
+        let builder = RecipeBuilder::generate(&recipe, vec!(DimensionTuple::unchecked(self.dimension_tuple.clone())));
+        for (coord, &i) in filled.iter() {
+            let shifted: HyperRectangle = self.boxes[i].hyper_rectangle.iter().zip(origin.iter()).map(|(interval, o)| {
+                Interval { begin: interval.begin - o, end: interval.end - o }
+            }).collect();
+            if builder.get_packing(0).get(coord) != Some(&shifted) {
+                return Err(BoxListError::Mismatch(coord.clone()))
+            }
+        }
+        Ok(recipe)
+    }
+}
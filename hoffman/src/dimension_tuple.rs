@@ -0,0 +1,244 @@
+use std::fmt;
+use std::ops::Deref;
+use std::collections::HashMap;
+
+use super::IntType;
+
+/// The sides `a1 < .. < an` of a brick.
+///
+/// `DimensionTuple::new` only accepts tuples in Hoffman's setting: positive,
+/// sorted, with unique subset sums and satisfying `a1 + .. + an < (n + 1) a1`.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DimensionTuple(Vec<IntType>);
+
+/// Two disjoint sets of side indices with equal sums.
+pub type Collision = (Vec<usize>, Vec<usize>);
+
+#[derive(Debug, PartialEq)]
+pub enum DimensionTupleError {
+    Empty,
+    /// Every side of a tuple allowing zero sides is zero.
+    AllZero,
+    NotPositive(IntType),
+    NotSorted(usize),
+    CollidingSums(Vec<Collision>),
+    Hoffman { sum: IntType, bound: IntType }
+}
+
+impl fmt::Display for DimensionTupleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DimensionTupleError::Empty => write!(f, "Dimension tuple is empty."),
+            DimensionTupleError::AllZero => write!(f, "Dimension tuple has no positive side."),
+            DimensionTupleError::NotPositive(side) => write!(f, "Side {} is not positive.", side),
+            DimensionTupleError::NotSorted(i) => write!(f, "Sides a{} and a{} are not in increasing order.", i + 1, i + 2),
+            DimensionTupleError::CollidingSums(collisions) => {
+                let collisions: Vec<String> = collisions.iter().map(|(lhs, rhs)| {
+                    format!("{} = {}", subset_str(lhs), subset_str(rhs))
+                }).collect();
+                write!(f, "Dimension tuple does not have unique sums: {}.", collisions.join(", "))
+            },
+            DimensionTupleError::Hoffman { sum, bound } => write!(f, "Sum {} is not below Hoffman's bound {}.", sum, bound)
+        }
+    }
+}
+
+fn subset_str(subset: &[usize]) -> String {
+    subset.iter().map(|i| format!("a{}", i + 1)).collect::<Vec<_>>().join(" + ")
+}
+
+impl DimensionTuple {
+    pub fn new(sides: Vec<IntType>) -> Result<DimensionTuple, DimensionTupleError> {
+        if sides.is_empty() {
+            return Err(DimensionTupleError::Empty)
+        }
+        if let Some(&side) = sides.iter().find(|&&side| side <= 0) {
+            return Err(DimensionTupleError::NotPositive(side))
+        }
+        if let Some(i) = sides.windows(2).position(|pair| pair[0] > pair[1]) {
+            return Err(DimensionTupleError::NotSorted(i))
+        }
+        let collisions = colliding_subsets(&sides);
+        if !collisions.is_empty() {
+            return Err(DimensionTupleError::CollidingSums(collisions))
+        }
+        let sum = sides.iter().sum();
+        let bound = (sides.len() as IntType + 1) * sides[0];
+        if sum >= bound {
+            return Err(DimensionTupleError::Hoffman { sum, bound })
+        }
+        Ok(DimensionTuple(sides))
+    }
+
+    /// A positive non-decreasing tuple that may repeat sides or have equal subset sums.
+    pub fn degenerate(sides: Vec<IntType>) -> Result<DimensionTuple, DimensionTupleError> {
+        if sides.is_empty() {
+            return Err(DimensionTupleError::Empty)
+        }
+        if let Some(&side) = sides.iter().find(|&&side| side <= 0) {
+            return Err(DimensionTupleError::NotPositive(side))
+        }
+        if let Some(i) = sides.windows(2).position(|pair| pair[0] > pair[1]) {
+            return Err(DimensionTupleError::NotSorted(i))
+        }
+        Ok(DimensionTuple(sides))
+    }
+
+    /// A non-negative non-decreasing tuple with some positive side, for
+    /// studying packings in the limit where the smallest sides vanish.
+    pub fn with_zero_sides(sides: Vec<IntType>) -> Result<DimensionTuple, DimensionTupleError> {
+        if sides.is_empty() {
+            return Err(DimensionTupleError::Empty)
+        }
+        if let Some(&side) = sides.iter().find(|&&side| side < 0) {
+            return Err(DimensionTupleError::NotPositive(side))
+        }
+        if let Some(i) = sides.windows(2).position(|pair| pair[0] > pair[1]) {
+            return Err(DimensionTupleError::NotSorted(i))
+        }
+        if sides.iter().all(|&side| side == 0) {
+            return Err(DimensionTupleError::AllZero)
+        }
+        Ok(DimensionTuple(sides))
+    }
+
+    /// A tuple outside Hoffman's setting, for experiments that need one.
+    pub fn unchecked(sides: Vec<IntType>) -> DimensionTuple {
+        DimensionTuple(sides)
+    }
+
+    pub fn sides(&self) -> &[IntType] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<IntType> {
+        self.0
+    }
+
+    /// The tuple divided by the greatest common divisor of its sides, which keeps every comparison of subset sums.
+    pub fn normalised(&self) -> DimensionTuple {
+        let divisor = self.0.iter().fold(0, |acc, &side| gcd(acc, side));
+        DimensionTuple(self.0.iter().map(|side| side / divisor.max(1)).collect())
+    }
+
+    /// All subsets of side indices, ordered by increasing sum.
+    pub fn subset_sum_order(&self) -> Vec<Vec<usize>> {
+        subset_sum_order(&self.0).iter().map(|&mask| mask_indices(mask)).collect()
+    }
+
+    /// The tuple with the smallest sum that orders all subset sums the same way.
+    ///
+    /// Candidates are searched by increasing sum, and the normalised tuple
+    /// bounds the search, so this is meant for the small tuples of the
+    /// backtracking binaries.
+    pub fn canonical(&self) -> DimensionTuple {
+        let normalised = self.normalised();
+        let order = subset_sum_order(&normalised);
+        let n = normalised.len();
+        let total: IntType = normalised.iter().sum();
+        for sum in (n * (n + 1) / 2) as IntType..=total {
+            let mut found = None;
+            hoffman_tuples_with_sum(n, sum, &mut |candidate| {
+                if found.is_none() && subset_sum_order(candidate) == order && colliding_subsets(candidate).is_empty() {
+                    found = Some(DimensionTuple(candidate.to_vec()));
+                }
+            });
+            if let Some(canonical) = found {
+                return canonical
+            }
+        }
+        normalised
+    }
+}
+
+impl Deref for DimensionTuple {
+    type Target = [IntType];
+
+    fn deref(&self) -> &[IntType] {
+        &self.0
+    }
+}
+
+impl AsRef<[IntType]> for DimensionTuple {
+    fn as_ref(&self) -> &[IntType] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for DimensionTuple {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The disjoint pairs of index subsets with equal sums, each pair listed once.
+pub fn colliding_subsets(sides: &[IntType]) -> Vec<Collision> {
+    let mut first_seen: HashMap<IntType, u64> = HashMap::new();
+    let mut collisions: Vec<(u64, u64)> = Vec::new();
+    for mask in 0..1u64 << sides.len() {
+        let sum = mask_sum(sides, mask);
+        match first_seen.get(&sum) {
+            Some(&other) => {
+                let common = other & mask;
+                let pair = (other & !common, mask & !common);
+                if !collisions.contains(&pair) {
+                    collisions.push(pair);
+                }
+            },
+            None => { first_seen.insert(sum, mask); }
+        }
+    }
+    collisions.iter().map(|&(a, b)| (mask_indices(a), mask_indices(b))).collect()
+}
+
+/// Hoffman's inequality `a1 + .. + an < (n + 1) a1`, with `a1` the smallest side.
+pub fn satisfies_hoffman(sides: &[IntType]) -> bool {
+    match sides.iter().min() {
+        Some(&smallest) => sides.iter().sum::<IntType>() < (sides.len() as IntType + 1) * smallest,
+        None => false
+    }
+}
+
+fn subset_sum_order(sides: &[IntType]) -> Vec<u64> {
+    let mut masks: Vec<u64> = (0..1u64 << sides.len()).collect();
+    masks.sort_by_key(|&mask| (mask_sum(sides, mask), mask));
+    masks
+}
+
+fn mask_sum(sides: &[IntType], mask: u64) -> IntType {
+    sides.iter().enumerate().filter(|&(i, _)| mask & (1 << i) != 0).map(|(_, side)| side).sum()
+}
+
+fn mask_indices(mask: u64) -> Vec<usize> {
+    (0..64).filter(|i| mask & (1 << i) != 0).collect()
+}
+
+/// Calls `visit` in lexicographic order with every strictly increasing tuple
+/// of length `n` and sum `sum` satisfying Hoffman's inequality.
+pub fn hoffman_tuples_with_sum<F: FnMut(&[IntType])>(n: usize, sum: IntType, visit: &mut F) {
+    // Hoffman's inequality asks for `a1 > sum / (n + 1)`.
+    let smallest = sum / (n as IntType + 1) + 1;
+    increasing_tuples(n, sum, smallest, &mut Vec::new(), visit);
+}
+
+/// Calls `visit` in lexicographic order with every strictly increasing tuple
+/// of length `n` and sum `sum` that extends `prefix` by sides of at least `smallest`.
+fn increasing_tuples<F: FnMut(&[IntType])>(n: usize, sum: IntType, smallest: IntType, prefix: &mut Vec<IntType>, visit: &mut F) {
+    let remaining = (n - prefix.len()) as IntType;
+    if remaining == 0 {
+        if sum == 0 { visit(prefix) }
+        return
+    }
+    // The remaining sides are at least `side, side + 1, ..`.
+    let mut side = smallest;
+    while remaining * side + remaining * (remaining - 1) / 2 <= sum {
+        prefix.push(side);
+        increasing_tuples(n, sum - side, side + 1, prefix, visit);
+        prefix.pop();
+        side += 1;
+    }
+}
+
+fn gcd(a: IntType, b: IntType) -> IntType {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+
+use super::*;
+use combinatorics::*;
+use utils::*;
+
+const LEVEL_LABELS: &[&str] = &["x", "y", "z", "w"];
+
+/// Why a solution of the formulation, read from a GAMS listing or from a
+/// `.sol` file of another solver, is not a recipe.
+#[derive(Debug, PartialEq)]
+pub enum SolutionError {
+    /// The file could not be read.
+    Read(String),
+    Parse(String),
+    Ambiguous(Coord),
+    Incomplete(Coord)
+}
+
+impl fmt::Display for SolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolutionError::Read(error) => write!(f, "Could not read solution: {}", error),
+            SolutionError::Parse(line) => write!(f, "Could not parse: {}", line),
+            SolutionError::Ambiguous(coord) => write!(f, "Brick at {:?} has an ambiguous orientation.", coord),
+            SolutionError::Incomplete(coord) => write!(f, "Brick at {:?} has an incomplete orientation.", coord)
+        }
+    }
+}
+
+/// One disjunct of the non-overlap condition of two diagonal neighbours:
+/// the first `terms_count` bricks on the line through `a` end before those
+/// on the line through `b` when `a_first`, and after them otherwise.
+pub struct Separation {
+    pub a: Coord,
+    pub b: Coord,
+    pub dimension: usize,
+    pub terms_count: usize,
+    pub a_first: bool
+}
+
+/// The separations for each pair of diagonal neighbours, one per dimension along which they differ.
+pub fn separations(n: usize) -> Vec<Vec<Separation>> {
+    let coords: Vec<Coord> = make_coords(&vec!(n; n));
+    let mut pairs = Vec::new();
+    for (i, a) in coords.iter().enumerate() {
+        for b in &coords[i + 1..] {
+            if !are_diagonal_neighbours(a, b) { continue }
+            pairs.push(a.iter().zip(b.iter()).enumerate().filter(|&(_, (x, y))| x != y).map(|(d, (&x, &y))| {
+                Separation {
+                    a: a.clone(),
+                    b: b.clone(),
+                    dimension: d,
+                    terms_count: x.min(y) + 1,
+                    a_first: x < y
+                }
+            }).collect());
+        }
+    }
+    pairs
+}
+
+/// The overlap equations of the big-M formulation.
+///
+/// Every separation gets an indicator `u` that, when set, forces the two
+/// bricks apart. The `or` equation demands one indicator per pair of
+/// neighbours, unless the slack `z` is used.
+pub struct OverlapEquations {
+    pub equations: Vec<String>,
+    pub inter_count: usize,
+    pub or_count: usize
+}
+
+impl OverlapEquations {
+    pub fn new(n: usize) -> OverlapEquations {
+        let mut overlaps = OverlapEquations { equations: Vec::new(), inter_count: 0, or_count: 0 };
+        for pair in separations(n) {
+            let mut logic_terms = Vec::new();
+            for separation in &pair {
+                overlaps.inter_count += 1;
+                let (ineq, sign) = if separation.a_first { ("=l=", "+") } else { ("=g=", "-") };
+                overlaps.equations.push(format!("inter_{}.. {} {} {} {} (1 - u('{}')) * M('{}');",
+                    overlaps.inter_count,
+                    level_terms(&separation.a, separation.dimension, separation.terms_count), ineq,
+                    level_terms(&separation.b, separation.dimension, separation.terms_count),
+                    sign, overlaps.inter_count, separation.terms_count));
+                logic_terms.push(format!("u('{}')", overlaps.inter_count));
+            }
+            overlaps.or_count += 1;
+            overlaps.equations.push(format!("or_{}.. {} + z('{}') =g= 1;", overlaps.or_count, logic_terms.join(" + "), overlaps.or_count));
+        }
+        overlaps
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        (1..=self.inter_count).map(|i| format!("inter_{}", i))
+            .chain((1..=self.or_count).map(|i| format!("or_{}", i)))
+            .collect()
+    }
+}
+
+/// Sum of the widths along `dimension` of the first `terms_count` bricks on the line through `coord`.
+fn level_terms(coord: &Coord, dimension: usize, terms_count: usize) -> String {
+    (0..terms_count).map(|i| {
+        let mut c = coord.clone();
+        c[dimension] = i;
+        format!("w({}, '{}')", quoted(&c), dimension + 1)
+    }).collect::<Vec<_>>().join(" + ")
+}
+
+fn quoted(coord: &Coord) -> String {
+    coord.iter().map(|v| format!("'{}'", v + 1)).collect::<Vec<_>>().join(", ")
+}
+
+fn axis_label(dimension: usize) -> String {
+    match LEVEL_LABELS.get(dimension) {
+        Some(label) => label.to_string(),
+        None => format!("i{}", dimension + 1)
+    }
+}
+
+/// Largest difference between two sums of `k` sides, bounding the overlap equations.
+pub fn overlap_bound(dimension_tuple: &DimensionTuple, k: usize) -> IntType {
+    let sums: Vec<IntType> = combinations(dimension_tuple, k).iter().map(|sides| sides.iter().sum()).collect();
+    sums.iter().max().unwrap() - sums.iter().min().unwrap()
+}
+
+/// Complete GAMS model of the `n`-dimensional packing problem for one dimension tuple.
+pub fn gams_model(dimension_tuple: &DimensionTuple) -> String {
+    let n = dimension_tuple.len();
+    let equations = OverlapEquations::new(n);
+    let levels: Vec<String> = (1..=n).map(|i| format!("i{}", i)).collect();
+    let all_levels = levels.join(", ");
+
+    let mut gms = String::new();
+    gms.push_str(&format!("$Title Hoffman Packing Problem {}D\n\n", n));
+
+    gms.push_str("Sets\n");
+    gms.push_str(&format!("  d   dimensions  / 1 * {} /\n", n));
+    gms.push_str(&format!("  s   sides       / 1 * {} /\n", n));
+    for (dim, level) in levels.iter().enumerate() {
+        gms.push_str(&format!("  {}  {}-levels    / 1 * {} /\n", level, axis_label(dim), n));
+    }
+    gms.push_str(&format!("  k   layers      / 1 * {} /\n", n - 1));
+    gms.push_str(&format!("  p   intersects  / 1 * {} /\n", equations.inter_count));
+    gms.push_str(&format!("  ovs overlaps    / 1 * {} /;\n\n", equations.or_count));
+
+    gms.push_str("Parameters\n\n");
+    gms.push_str("  a(s)  dimensions of brick\n");
+    let sides: Vec<String> = dimension_tuple.iter().enumerate().map(|(i, a)| format!("{}  {}", i + 1, a)).collect();
+    gms.push_str(&format!("    / {} /\n\n", sides.join("\n      ")));
+    gms.push_str("  M(k)  overlap bound\n");
+    let bounds: Vec<String> = (1..n).map(|k| format!("{}  {}", k, overlap_bound(dimension_tuple, k))).collect();
+    gms.push_str(&format!("    / {} /;\n\n", bounds.join("\n      ")));
+
+    gms.push_str("Variables\n");
+    gms.push_str(&format!("  x({}, d, s)  is there a length a(s) along dimension d at ({})\n", all_levels, levels.join(" ")));
+    gms.push_str(&format!("  w({}, d)     length along dimension d at ({})\n", all_levels, levels.join(" ")));
+    gms.push_str("  u(p)                 interval intersection indicators\n");
+    gms.push_str("  z(ovs)               overlaps to prevent\n");
+    gms.push_str("  q                    what to optimize;\n\n");
+    gms.push_str("Binary Variable x, u;\n");
+    gms.push_str("Positive Variable w, z;\n\n");
+
+    gms.push_str("Equations\n");
+    gms.push_str(&format!("  unique_length({}, d)  brick has only one length in each direction\n", all_levels));
+    gms.push_str(&format!("  brick({}, s)   brick has only one dimension with each length\n", all_levels));
+    for (dim, level) in levels.iter().enumerate() {
+        let others = list_except(&levels, std::slice::from_ref(level)).join(", ");
+        gms.push_str(&format!("  line{}({}, s)     unique line along {} dimension\n", dim + 1, others, axis_label(dim)));
+    }
+    gms.push_str(&format!("  length({}, d)  compute brick dimensions\n", all_levels));
+    gms.push_str("  anding                    combine overlaps;\n\n");
+
+    gms.push_str(&format!("unique_length({0}, d) ..  1 =e= sum(s, x({0}, d, s));\n", all_levels));
+    gms.push_str(&format!("brick({0}, s) ..          1 =e= sum(d, x({0}, d, s));\n", all_levels));
+    for (dim, level) in levels.iter().enumerate() {
+        let others = list_except(&levels, std::slice::from_ref(level)).join(", ");
+        gms.push_str(&format!("line{}({}, s) ..              1 =e= sum({}, x({}, '{}', s));\n", dim + 1, others, level, all_levels, dim + 1));
+    }
+    gms.push_str(&format!("length({0}, d) ..         w({0}, d) =e= sum(s, x({0}, d, s) * a(s));\n", all_levels));
+    gms.push_str("anding ..                        q =e= sum(ovs, z(ovs));\n\n");
+
+    gms.push_str("Equations\n");
+    gms.push_str(&format!("{};\n\n", equations.names().join("\n")));
+    for equation in &equations.equations {
+        gms.push_str(&format!("{}\n", equation));
+    }
+    gms.push_str(&format!("\nModel hoffman_{}d /all/;\n", n));
+    gms.push_str("Option Reslim=1000000;\n");
+    gms.push_str(&format!("hoffman_{}d.OptFile=1;\n", n));
+    gms.push_str(&format!("Solve hoffman_{}d using mip minimizing q;\n\n", n));
+    gms.push_str("Display w.l;\n");
+    gms
+}
+
+pub fn save_gams(dimension_tuple: &DimensionTuple, directory: &String, file_name: &String) {
+    utils::write_file(&gams_model(dimension_tuple), &format!("exports/{}", directory), &format!("{}.gms", file_name))
+      .expect("Error writing GAMS model to file");
+}
+
+/// Reads the levels of `x(i1, .., in, d, s)` from the solution report of a `.lst` listing.
+pub fn parse_listing(n: usize, listing: &str) -> Result<Recipe, SolutionError> {
+    let mut chosen = Vec::new();
+    let mut in_x = false;
+    for line in listing.lines() {
+        let trimmed = line.trim_start_matches('-').trim();
+        if let Some(rest) = trimmed.strip_prefix("VAR ") {
+            in_x = rest.split_whitespace().next() == Some("x");
+            continue
+        }
+        if !in_x { continue }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let indices: Vec<usize> = match words.first().map(|w| w.split('.').map(|v| v.parse::<usize>()).collect()) {
+            Some(Ok(indices)) => indices,
+            _ => continue
+        };
+        let level = match words.get(2) {
+            Some(&".") => 0.0,
+            Some(value) => value.parse::<f64>().map_err(|_| SolutionError::Parse(line.to_string()))?,
+            None => return Err(SolutionError::Parse(line.to_string()))
+        };
+        if level >= 0.5 {
+            chosen.push((indices, line.to_string()));
+        }
+    }
+    recipe_from_indices(n, &chosen)
+}
+
+/// Assembles a recipe from the one-based indices `(i1, .., in, d, s)` of the
+/// `x` variables set to one, each given along with the line it was read from.
+pub fn recipe_from_indices(n: usize, chosen: &[(Vec<usize>, String)]) -> Result<Recipe, SolutionError> {
+    let mut recipe = Recipe::new(n, n);
+    let mut orientations: Vec<Vec<Option<usize>>> = vec!(vec!(None; n); recipe.map.coords().len());
+    for (indices, line) in chosen {
+        if indices.len() != n + 2 || indices.contains(&0) || indices.iter().any(|&i| i > n) {
+            return Err(SolutionError::Parse(line.clone()))
+        }
+        let coord: Coord = indices[..n].iter().map(|i| i - 1).collect();
+        let index = recipe.map.index(&coord);
+        let side = &mut orientations[index][indices[n] - 1];
+        if side.is_some() {
+            return Err(SolutionError::Ambiguous(coord))
+        }
+        *side = Some(indices[n + 1] - 1);
+    }
+    for (index, orientation) in orientations.iter().enumerate() {
+        let coord = recipe.map.coord(index).clone();
+        let orientation: Orientation = match orientation.iter().cloned().collect::<Option<Vec<usize>>>() {
+            Some(orientation) => orientation,
+            None => return Err(SolutionError::Incomplete(coord))
+        };
+        if (0..n).any(|side| !orientation.contains(&side)) {
+            return Err(SolutionError::Ambiguous(coord))
+        }
+        recipe.map.insert(&coord, orientation);
+    }
+    Ok(recipe)
+}
+
+pub fn load_listing(n: usize, directory: &String, file_name: &String) -> Result<Recipe, SolutionError> {
+    let listing = utils::read_file(directory, &format!("{}.lst", file_name))
+      .map_err(|e| SolutionError::Read(e.to_string()))?;
+    parse_listing(n, &listing)
+}
@@ -1,13 +1,14 @@
 use super::IntType;
+use number::Number;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Interval {
-    pub begin: IntType,
-    pub end: IntType
+pub struct Interval<T = IntType> {
+    pub begin: T,
+    pub end: T
 }
 
-impl Interval {
-    pub fn intersects(&self, other: &Interval) -> bool {
+impl<T: Number> Interval<T> {
+    pub fn intersects(&self, other: &Interval<T>) -> bool {
         if self.begin < other.begin {
             other.begin < self.end
         } else {
@@ -15,8 +16,14 @@ impl Interval {
         }
     }
 
-    pub fn width(&self) -> IntType {
-        self.end - self.begin
+    /// Whether the interiors meet. A degenerate interval has no interior,
+    /// so unlike `intersects` it overlaps nothing.
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        !self.is_degenerate() && !other.is_degenerate() && self.intersects(other)
+    }
+
+    pub fn width(&self) -> T {
+        self.end.clone() - self.begin.clone()
     }
 
     pub fn is_degenerate(&self) -> bool {
@@ -24,6 +31,6 @@ impl Interval {
     }
 
     pub fn is_zero(&self) -> bool {
-        self.begin == 0 && self.end == 0
+        self.begin == T::zero() && self.end == T::zero()
     }
 }
@@ -1,25 +1,49 @@
 extern crate serde_json;
 extern crate itertools;
+extern crate num_bigint;
+extern crate num_rational;
+extern crate num_traits;
+extern crate memmap2;
 
 pub mod ndarray;
+pub mod number;
 pub mod utils;
+pub mod dimension_tuple;
 pub mod combinatorics;
 pub mod interval;
 pub mod recipe;
 pub mod recipe_builder;
 pub mod plot;
+pub mod sat;
+pub mod gams;
+pub mod milp;
+pub mod symbolic;
+pub mod sweep;
+pub mod simplex;
+pub mod signatures;
+pub mod validity;
+pub mod region;
+pub mod verify;
+pub mod boxes;
+pub mod binary;
+pub mod archive;
+pub mod sink;
+pub mod npy;
+pub mod mesh;
+pub mod scad;
 
 #[cfg(test)]
 mod tests;
 
 pub use ndarray::*;
+pub use number::Number;
 pub use interval::*;
+pub use dimension_tuple::*;
 pub use recipe::*;
 pub use recipe_builder::*;
 
 pub type IntType = i32;
 pub type Coord = Vec<usize>;
 pub type Shape = Vec<usize>;
-pub type DimensionTuple = Vec<IntType>;
 pub type Orientation = Vec<usize>;
-pub type HyperRectangle = Vec<Interval>;
+pub type HyperRectangle<T = IntType> = Vec<Interval<T>>;
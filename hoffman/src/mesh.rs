@@ -0,0 +1,264 @@
+use super::*;
+
+/// Corners of the unit cube as offsets along the three axes, indexed `i + 2j + 4k`.
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0], [1, 0, 0], [0, 1, 0], [1, 1, 0],
+    [0, 0, 1], [1, 0, 1], [0, 1, 1], [1, 1, 1]
+];
+
+/// Faces of the unit cube as corners in counter-clockwise order seen from
+/// outside, with their outward normals.
+const FACES: [([usize; 4], [f32; 3]); 6] = [
+    ([0, 4, 6, 2], [-1.0, 0.0, 0.0]),
+    ([1, 3, 7, 5], [1.0, 0.0, 0.0]),
+    ([0, 1, 5, 4], [0.0, -1.0, 0.0]),
+    ([2, 6, 7, 3], [0.0, 1.0, 0.0]),
+    ([0, 2, 3, 1], [0.0, 0.0, -1.0]),
+    ([4, 5, 7, 6], [0.0, 0.0, 1.0])
+];
+
+/// How `Mesh::new` places the boxes.
+#[derive(Clone, Debug)]
+pub struct MeshOptions {
+    /// The axes of the packing that become the x, y and z axes of the mesh.
+    pub axes: [usize; 3],
+    /// Levels of the axes not exported, which are at level 0 unless given.
+    pub levels: Vec<(usize, usize)>,
+    /// Length in the output of a unit of the sides.
+    pub scale: f64,
+    /// Space added between neighbouring boxes, in units of the sides.
+    pub gap: f64,
+    /// Amount taken off every face of a box, in units of the sides, so that
+    /// printed pieces fit together.
+    pub shrink: f64
+}
+
+impl Default for MeshOptions {
+    fn default() -> MeshOptions {
+        MeshOptions { axes: [0, 1, 2], levels: Vec::new(), scale: 1.0, gap: 0.0, shrink: 0.0 }
+    }
+}
+
+/// A box of a packing as a separate object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshBox {
+    /// `box` followed by the grid coordinates.
+    pub name: String,
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+    /// Index of the sides along the three axes among the arrangements of
+    /// three of the `n` sides, after merging equal sides.
+    pub class: usize
+}
+
+impl MeshBox {
+    fn corner(&self, corner: usize) -> [f32; 3] {
+        let offsets = CORNERS[corner];
+        [0, 1, 2].map(|i| (if offsets[i] == 0 { self.min[i] } else { self.max[i] }) as f32)
+    }
+
+    /// The twelve triangles of the box with their normals.
+    fn triangles(&self) -> Vec<([f32; 3], [[f32; 3]; 3])> {
+        FACES.iter().flat_map(|&(corners, normal)| {
+            let [a, b, c, d] = corners.map(|corner| self.corner(corner));
+            vec!((normal, [a, b, c]), (normal, [a, c, d]))
+        }).collect()
+    }
+}
+
+/// The boxes of a three-dimensional packing, or of a three-dimensional slice
+/// of a larger one, coloured by orientation class.
+#[derive(Clone, Debug)]
+pub struct Mesh {
+    pub boxes: Vec<MeshBox>,
+    /// The colour of every class.
+    pub colors: Vec<(u8, u8, u8)>
+}
+
+impl Mesh {
+    pub fn new<T: Number>(recipe_builder: &RecipeBuilder<T>, index: usize, options: &MeshOptions) -> Mesh {
+        let recipe = recipe_builder.get_recipe();
+        let n = recipe.n;
+        let packing = recipe_builder.get_packing(index);
+        let boxes = recipe_builder.slice_coords(&options.axes, &options.levels).into_iter().map(|coord| {
+            let hyper_rectangle = packing.get(&coord).unwrap();
+            let orientation = recipe::merge_orientation(recipe.map.get(&coord).unwrap(), recipe_builder.classes());
+            let sides: Vec<usize> = options.axes.iter().map(|&axis| orientation[axis]).collect();
+            let bound = |i: usize, value: &T, inward: f64| {
+                (value.to_f64() + options.gap * coord[options.axes[i]] as f64 + inward) * options.scale
+            };
+            MeshBox {
+                name: format!("box {}", coord.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ")),
+                min: [0, 1, 2].map(|i| bound(i, &hyper_rectangle[options.axes[i]].begin, options.shrink)),
+                max: [0, 1, 2].map(|i| bound(i, &hyper_rectangle[options.axes[i]].end, -options.shrink)),
+                class: binary::orientation_rank(&sides, n) as usize
+            }
+        }).collect();
+        Mesh { boxes, colors: plot::rgb_palette((0..3).map(|i| n - i).product()) }
+    }
+
+    /// One solid per box.
+    pub fn to_stl(&self) -> String {
+        let mut stl = String::new();
+        for mesh_box in &self.boxes {
+            let name = mesh_box.name.replace(' ', "_");
+            stl.push_str(&format!("solid {}\n", name));
+            for (normal, vertices) in mesh_box.triangles() {
+                stl.push_str(&format!("  facet normal {} {} {}\n    outer loop\n", normal[0], normal[1], normal[2]));
+                for vertex in &vertices {
+                    stl.push_str(&format!("      vertex {} {} {}\n", vertex[0], vertex[1], vertex[2]));
+                }
+                stl.push_str("    endloop\n  endfacet\n");
+            }
+            stl.push_str(&format!("endsolid {}\n", name));
+        }
+        stl
+    }
+
+    /// Binary STL holds a single object, so the boxes are told apart only by
+    /// the colour in the attribute of every triangle, five bits per channel
+    /// with the top bit set as in VisCAM and SolidView.
+    pub fn to_binary_stl(&self) -> Vec<u8> {
+        let mut header = b"hoffman packing".to_vec();
+        header.resize(80, b' ');
+        let mut bytes = header;
+        bytes.extend_from_slice(&(12 * self.boxes.len() as u32).to_le_bytes());
+        for mesh_box in &self.boxes {
+            let (r, g, b) = self.colors[mesh_box.class];
+            let attribute = 0x8000 | (r as u16 >> 3) << 10 | (g as u16 >> 3) << 5 | b as u16 >> 3;
+            for (normal, vertices) in mesh_box.triangles() {
+                for value in normal.iter().chain(vertices.iter().flatten()) {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+                bytes.extend_from_slice(&attribute.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// The OBJ file, which refers to the materials as `{material_file}.mtl`, and the MTL file.
+    pub fn to_obj(&self, material_file: &str) -> (String, String) {
+        let mut obj = format!("mtllib {}.mtl\n", material_file);
+        for (i, mesh_box) in self.boxes.iter().enumerate() {
+            obj.push_str(&format!("o {}\nusemtl class_{}\n", mesh_box.name.replace(' ', "_"), mesh_box.class));
+            for corner in 0..8 {
+                let [x, y, z] = mesh_box.corner(corner);
+                obj.push_str(&format!("v {} {} {}\n", x, y, z));
+            }
+            for (corners, _) in FACES.iter() {
+                let indices: Vec<String> = corners.iter().map(|corner| (8 * i + corner + 1).to_string()).collect();
+                obj.push_str(&format!("f {}\n", indices.join(" ")));
+            }
+        }
+        let mut mtl = String::new();
+        let mut classes: Vec<usize> = self.boxes.iter().map(|mesh_box| mesh_box.class).collect();
+        classes.sort();
+        classes.dedup();
+        for class in classes {
+            let (r, g, b) = self.colors[class];
+            mtl.push_str(&format!("newmtl class_{}\nKd {:.4} {:.4} {:.4}\n", class, r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0));
+        }
+        (obj, mtl)
+    }
+
+    /// A glTF 2.0 scene with a node and a mesh for every box and a material
+    /// for every class, with the geometry embedded as a base64 data URI.
+    ///
+    /// The buffer holds the 36 indices of a box, then the normals of its 24
+    /// vertices, four per face, which all boxes share, and then the positions
+    /// of the vertices of every box.
+    pub fn to_gltf(&self) -> String {
+        let mut buffer: Vec<u8> = Vec::new();
+        for face in 0..6u16 {
+            for corner in &[0, 1, 2, 0, 2, 3] {
+                buffer.extend_from_slice(&(4 * face + corner).to_le_bytes());
+            }
+        }
+        for (_, normal) in FACES.iter() {
+            for _ in 0..4 {
+                normal.iter().for_each(|value| buffer.extend_from_slice(&value.to_le_bytes()));
+            }
+        }
+        let positions_offset = buffer.len();
+        for mesh_box in &self.boxes {
+            for (corners, _) in FACES.iter() {
+                for &corner in corners {
+                    mesh_box.corner(corner).iter().for_each(|value| buffer.extend_from_slice(&value.to_le_bytes()));
+                }
+            }
+        }
+
+        let box_size = 24 * 12;
+        let float_list = |values: [f32; 3]| format!("[{}, {}, {}]", values[0], values[1], values[2]);
+        let mut accessors = vec!(
+            String::from("{ \"bufferView\": 0, \"componentType\": 5123, \"count\": 36, \"type\": \"SCALAR\" }"),
+            String::from("{ \"bufferView\": 1, \"componentType\": 5126, \"count\": 24, \"type\": \"VEC3\" }")
+        );
+        let mut meshes = Vec::new();
+        let mut nodes = Vec::new();
+        let mut classes: Vec<usize> = self.boxes.iter().map(|mesh_box| mesh_box.class).collect();
+        classes.sort();
+        classes.dedup();
+        for (i, mesh_box) in self.boxes.iter().enumerate() {
+            accessors.push(format!("{{ \"bufferView\": 2, \"byteOffset\": {}, \"componentType\": 5126, \"count\": 24, \"type\": \"VEC3\", \"min\": {}, \"max\": {} }}",
+                i * box_size, float_list(mesh_box.corner(0)), float_list(mesh_box.corner(7))));
+            let material = classes.iter().position(|&class| class == mesh_box.class).unwrap();
+            meshes.push(format!("{{ \"name\": \"{}\", \"primitives\": [{{ \"attributes\": {{ \"POSITION\": {}, \"NORMAL\": 1 }}, \"indices\": 0, \"material\": {} }}] }}",
+                mesh_box.name, i + 2, material));
+            nodes.push(format!("{{ \"name\": \"{}\", \"mesh\": {} }}", mesh_box.name, i));
+        }
+        let materials: Vec<String> = classes.iter().map(|&class| {
+            let (r, g, b) = self.colors[class];
+            format!("{{ \"name\": \"class {}\", \"pbrMetallicRoughness\": {{ \"baseColorFactor\": [{:.4}, {:.4}, {:.4}, 1.0], \"metallicFactor\": 0.0 }} }}",
+                class, r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0)
+        }).collect();
+        let buffer_views = [
+            String::from("{ \"buffer\": 0, \"byteOffset\": 0, \"byteLength\": 72, \"target\": 34963 }"),
+            format!("{{ \"buffer\": 0, \"byteOffset\": 72, \"byteLength\": {}, \"target\": 34962 }}", box_size),
+            format!("{{ \"buffer\": 0, \"byteOffset\": {}, \"byteLength\": {}, \"target\": 34962 }}", positions_offset, buffer.len() - positions_offset)
+        ];
+        let scene: Vec<String> = (0..self.boxes.len()).map(|i| i.to_string()).collect();
+        format!("{{ \"asset\": {{ \"version\": \"2.0\", \"generator\": \"hoffman\" }},\n\"scene\": 0,\n\"scenes\": [{{ \"nodes\": [{}] }}],\n\"nodes\": [\n{}\n],\n\"meshes\": [\n{}\n],\n\"materials\": [\n{}\n],\n\"accessors\": [\n{}\n],\n\"bufferViews\": [\n{}\n],\n\"buffers\": [{{ \"byteLength\": {}, \"uri\": \"data:application/octet-stream;base64,{}\" }}] }}",
+            scene.join(", "), nodes.join(",\n"), meshes.join(",\n"), materials.join(",\n"), accessors.join(",\n"),
+            buffer_views.join(",\n"), buffer.len(), base64(&buffer))
+    }
+
+    pub fn save_stl(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_stl(), &format!("exports/{}", directory), &format!("{}.stl", file_name))
+          .expect("Error writing mesh to file");
+    }
+
+    pub fn save_binary_stl(&self, directory: &String, file_name: &String) {
+        utils::write_bytes(&self.to_binary_stl(), &format!("exports/{}", directory), &format!("{}.stl", file_name))
+          .expect("Error writing mesh to file");
+    }
+
+    pub fn save_obj(&self, directory: &String, file_name: &String) {
+        let (obj, mtl) = self.to_obj(file_name);
+        utils::write_file(&obj, &format!("exports/{}", directory), &format!("{}.obj", file_name))
+          .expect("Error writing mesh to file");
+        utils::write_file(&mtl, &format!("exports/{}", directory), &format!("{}.mtl", file_name))
+          .expect("Error writing mesh to file");
+    }
+
+    pub fn save_gltf(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_gltf(), &format!("exports/{}", directory), &format!("{}.gltf", file_name))
+          .expect("Error writing mesh to file");
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (i, &byte)| word | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(word >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use super::*;
+use gams::*;
+
+/// Variables per line in the LP format, keeping lines well below solver limits.
+const TERMS_PER_LINE: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sense {
+    LessEqual,
+    Equal,
+    GreaterEqual
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Binary,
+    Positive,
+    Free
+}
+
+pub struct Constraint {
+    pub name: String,
+    pub terms: Vec<(usize, IntType)>,
+    pub sense: Sense,
+    pub rhs: IntType
+}
+
+/// The big-M formulation of the gams binary as an explicit mixed integer program.
+///
+/// Variable and equation names follow the GAMS model with one-based indices,
+/// e.g. `x(i1, i2, i3, d, s)` becomes `x_1_2_3_1_2` and `inter_7` stays `inter_7`.
+pub struct MilpModel {
+    pub name: String,
+    pub dimension_tuple: DimensionTuple,
+    pub variables: Vec<(String, Kind)>,
+    pub constraints: Vec<Constraint>,
+    pub objective: usize,
+    indices: HashMap<String, usize>
+}
+
+fn subscript(indices: &[usize]) -> String {
+    indices.iter().map(|i| (i + 1).to_string()).collect::<Vec<_>>().join("_")
+}
+
+fn x_name(coord: &Coord, d: usize, s: usize) -> String {
+    format!("x_{}_{}_{}", subscript(coord), d + 1, s + 1)
+}
+
+fn w_name(coord: &Coord, d: usize) -> String {
+    format!("w_{}_{}", subscript(coord), d + 1)
+}
+
+impl MilpModel {
+    pub fn new(dimension_tuple: &DimensionTuple) -> MilpModel {
+        let n = dimension_tuple.len();
+        let mut model = MilpModel {
+            name: format!("hoffman_{}d", n),
+            dimension_tuple: dimension_tuple.clone(),
+            variables: Vec::new(),
+            constraints: Vec::new(),
+            objective: 0,
+            indices: HashMap::new()
+        };
+        let coords = utils::make_coords(&vec!(n; n));
+        let levels: Vec<usize> = (0..n).collect();
+
+        for coord in &coords {
+            for d in 0..n {
+                model.add_constraint(format!("unique_length_{}_{}", subscript(coord), d + 1),
+                    (0..n).map(|s| (x_name(coord, d, s), 1)).collect(), Sense::Equal, 1);
+            }
+            for s in 0..n {
+                model.add_constraint(format!("brick_{}_{}", subscript(coord), s + 1),
+                    (0..n).map(|d| (x_name(coord, d, s), 1)).collect(), Sense::Equal, 1);
+            }
+        }
+        for d in 0..n {
+            for coord in coords.iter().filter(|coord| coord[d] == 0) {
+                let others: Vec<usize> = utils::list_except(&levels, &[d]).iter().map(|&i| coord[i]).collect();
+                for s in 0..n {
+                    let terms = (0..n).map(|level| {
+                        let mut c = coord.clone();
+                        c[d] = level;
+                        (x_name(&c, d, s), 1)
+                    }).collect();
+                    model.add_constraint(format!("line{}_{}_{}", d + 1, subscript(&others), s + 1), terms, Sense::Equal, 1);
+                }
+            }
+        }
+        for coord in &coords {
+            for d in 0..n {
+                let mut terms = vec!((w_name(coord, d), 1));
+                terms.extend((0..n).map(|s| (x_name(coord, d, s), -dimension_tuple[s])));
+                model.add_constraint(format!("length_{}_{}", subscript(coord), d + 1), terms, Sense::Equal, 0);
+            }
+        }
+
+        let mut u_index = 0;
+        let mut z_terms = Vec::new();
+        for (pair_index, pair) in separations(n).iter().enumerate() {
+            let mut or_terms = Vec::new();
+            for separation in pair {
+                u_index += 1;
+                let bound = overlap_bound(dimension_tuple, separation.terms_count);
+                let sign = if separation.a_first { 1 } else { -1 };
+                let mut terms: Vec<(String, IntType)> = Vec::new();
+                for level in 0..separation.terms_count {
+                    let (mut a, mut b) = (separation.a.clone(), separation.b.clone());
+                    a[separation.dimension] = level;
+                    b[separation.dimension] = level;
+                    terms.push((w_name(&a, separation.dimension), 1));
+                    terms.push((w_name(&b, separation.dimension), -1));
+                }
+                terms.push((format!("u_{}", u_index), sign * bound));
+                let sense = if separation.a_first { Sense::LessEqual } else { Sense::GreaterEqual };
+                model.add_constraint(format!("inter_{}", u_index), terms, sense, sign * bound);
+                or_terms.push((format!("u_{}", u_index), 1));
+            }
+            let z = format!("z_{}", pair_index + 1);
+            or_terms.push((z.clone(), 1));
+            model.add_constraint(format!("or_{}", pair_index + 1), or_terms, Sense::GreaterEqual, 1);
+            z_terms.push((z, -1));
+        }
+        let mut anding = vec!((String::from("q"), 1));
+        anding.extend(z_terms);
+        model.add_constraint(String::from("anding"), anding, Sense::Equal, 0);
+        model.objective = model.indices["q"];
+        model
+    }
+
+    fn variable(&mut self, name: String) -> usize {
+        if let Some(&index) = self.indices.get(&name) {
+            return index
+        }
+        let kind = match name.chars().next() {
+            Some('x') | Some('u') => Kind::Binary,
+            Some('q') => Kind::Free,
+            _ => Kind::Positive
+        };
+        self.variables.push((name.clone(), kind));
+        self.indices.insert(name, self.variables.len() - 1);
+        self.variables.len() - 1
+    }
+
+    fn add_constraint(&mut self, name: String, terms: Vec<(String, IntType)>, sense: Sense, rhs: IntType) {
+        let terms = terms.into_iter().map(|(variable, coefficient)| (self.variable(variable), coefficient)).collect();
+        self.constraints.push(Constraint { name, terms, sense, rhs });
+    }
+
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).cloned()
+    }
+
+    /// The values of all variables corresponding to the recipe, with `q`
+    /// counting the pairs of neighbours that overlap. This is a feasible
+    /// point for any complete recipe and can be used as a MIP start.
+    pub fn assignment(&self, recipe: &Recipe) -> Vec<IntType> {
+        let n = self.dimension_tuple.len();
+        let mut values = vec!(0; self.variables.len());
+        for (coord, orientation) in recipe.map.iter() {
+            for (d, &s) in orientation.iter().enumerate() {
+                values[self.indices[&x_name(coord, d, s)]] = 1;
+                values[self.indices[&w_name(coord, d)]] = self.dimension_tuple[s];
+            }
+        }
+        let width = |values: &Vec<IntType>, coord: &Coord, d: usize, terms_count: usize| -> IntType {
+            (0..terms_count).map(|level| {
+                let mut c = coord.clone();
+                c[d] = level;
+                values[self.indices[&w_name(&c, d)]]
+            }).sum()
+        };
+        let mut u_index = 0;
+        for (pair_index, pair) in separations(n).iter().enumerate() {
+            let mut separated = false;
+            for separation in pair {
+                u_index += 1;
+                let a = width(&values, &separation.a, separation.dimension, separation.terms_count);
+                let b = width(&values, &separation.b, separation.dimension, separation.terms_count);
+                if (separation.a_first && a <= b) || (!separation.a_first && a >= b) {
+                    values[self.indices[&format!("u_{}", u_index)]] = 1;
+                    separated = true;
+                }
+            }
+            if !separated {
+                values[self.indices[&format!("z_{}", pair_index + 1)]] = 1;
+                values[self.objective] += 1;
+            }
+        }
+        values
+    }
+
+    pub fn violated_constraints(&self, values: &[IntType]) -> Vec<&str> {
+        self.constraints.iter().filter(|constraint| {
+            let lhs: IntType = constraint.terms.iter().map(|&(i, coefficient)| coefficient * values[i]).sum();
+            match constraint.sense {
+                Sense::LessEqual => lhs > constraint.rhs,
+                Sense::Equal => lhs != constraint.rhs,
+                Sense::GreaterEqual => lhs < constraint.rhs
+            }
+        }).map(|constraint| constraint.name.as_str()).collect()
+    }
+
+    pub fn to_lp(&self) -> String {
+        let mut lp = String::new();
+        lp.push_str(&format!("\\ Hoffman Packing Problem {}D, dimension tuple {:?}\n", self.dimension_tuple.len(), self.dimension_tuple));
+        lp.push_str("Minimize\n");
+        lp.push_str(&format!(" obj: {}\n", self.variables[self.objective].0));
+        lp.push_str("Subject To\n");
+        for constraint in &self.constraints {
+            let terms: Vec<String> = constraint.terms.iter().enumerate().map(|(i, &(variable, coefficient))| {
+                let sign = if coefficient < 0 { "- " } else if i == 0 { "" } else { "+ " };
+                let magnitude = if coefficient.abs() == 1 { String::new() } else { format!("{} ", coefficient.abs()) };
+                format!("{}{}{}", sign, magnitude, self.variables[variable].0)
+            }).collect();
+            let lines: Vec<String> = terms.chunks(TERMS_PER_LINE).map(|chunk| chunk.join(" ")).collect();
+            let sense = match constraint.sense {
+                Sense::LessEqual => "<=",
+                Sense::Equal => "=",
+                Sense::GreaterEqual => ">="
+            };
+            lp.push_str(&format!(" {}: {} {} {}\n", constraint.name, lines.join("\n   "), sense, constraint.rhs));
+        }
+        lp.push_str("Bounds\n");
+        for (name, _) in self.variables.iter().filter(|&(_, kind)| *kind == Kind::Free) {
+            lp.push_str(&format!(" {} free\n", name));
+        }
+        lp.push_str("Binaries\n");
+        let binaries: Vec<&str> = self.variables.iter().filter(|&(_, kind)| *kind == Kind::Binary).map(|(name, _)| name.as_str()).collect();
+        for chunk in binaries.chunks(TERMS_PER_LINE) {
+            lp.push_str(&format!(" {}\n", chunk.join(" ")));
+        }
+        lp.push_str("End\n");
+        lp
+    }
+
+    /// The model in free MPS format, with binaries declared through `BV` bounds.
+    pub fn to_mps(&self) -> String {
+        let mut columns: Vec<Vec<(&str, IntType)>> = vec!(Vec::new(); self.variables.len());
+        columns[self.objective].push(("obj", 1));
+        for constraint in &self.constraints {
+            for &(variable, coefficient) in &constraint.terms {
+                columns[variable].push((constraint.name.as_str(), coefficient));
+            }
+        }
+
+        let mut mps = String::new();
+        mps.push_str(&format!("NAME {}\n", self.name));
+        mps.push_str("ROWS\n");
+        mps.push_str(" N obj\n");
+        for constraint in &self.constraints {
+            let sense = match constraint.sense {
+                Sense::LessEqual => "L",
+                Sense::Equal => "E",
+                Sense::GreaterEqual => "G"
+            };
+            mps.push_str(&format!(" {} {}\n", sense, constraint.name));
+        }
+        mps.push_str("COLUMNS\n");
+        for ((name, _), column) in self.variables.iter().zip(columns.iter()) {
+            for (row, coefficient) in column {
+                mps.push_str(&format!("    {} {} {}\n", name, row, coefficient));
+            }
+        }
+        mps.push_str("RHS\n");
+        for constraint in self.constraints.iter().filter(|constraint| constraint.rhs != 0) {
+            mps.push_str(&format!("    RHS {} {}\n", constraint.name, constraint.rhs));
+        }
+        mps.push_str("BOUNDS\n");
+        for (name, kind) in &self.variables {
+            match kind {
+                Kind::Binary => mps.push_str(&format!(" BV BND {}\n", name)),
+                Kind::Free => mps.push_str(&format!(" FR BND {}\n", name)),
+                Kind::Positive => continue
+            }
+        }
+        mps.push_str("ENDATA\n");
+        mps
+    }
+
+    /// A solution file with one `name value` line per variable, as written by HiGHS, Gurobi and SCIP.
+    pub fn to_solution(&self, values: &[IntType]) -> String {
+        let mut sol = String::new();
+        sol.push_str(&format!("# Objective value = {}\n", values[self.objective]));
+        for ((name, _), value) in self.variables.iter().zip(values.iter()) {
+            sol.push_str(&format!("{} {}\n", name, value));
+        }
+        sol
+    }
+
+    pub fn save_lp(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_lp(), &format!("exports/{}", directory), &format!("{}.lp", file_name))
+          .expect("Error writing LP model to file");
+    }
+
+    pub fn save_mps(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_mps(), &format!("exports/{}", directory), &format!("{}.mps", file_name))
+          .expect("Error writing MPS model to file");
+    }
+}
+
+/// Reads the `x` variables set to one from a `.sol` file.
+///
+/// Accepts the `name value` lines of HiGHS, Gurobi and SCIP, the
+/// `index name value ...` lines of CBC and the XML of CPLEX.
+pub fn parse_solution(n: usize, contents: &str) -> Result<Recipe, SolutionError> {
+    let mut chosen = Vec::new();
+    for line in contents.lines() {
+        let (name, value) = if line.contains("name=\"") {
+            match (xml_attribute(line, "name"), xml_attribute(line, "value")) {
+                (Some(name), Some(value)) => (name, value),
+                _ => continue
+            }
+        } else {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.iter().position(|word| word.starts_with("x_")) {
+                Some(i) if i + 1 < words.len() => (words[i], words[i + 1]),
+                _ => continue
+            }
+        };
+        if !name.starts_with("x_") { continue }
+        let indices: Vec<usize> = match name["x_".len()..].split('_').map(|v| v.parse()).collect() {
+            Ok(indices) => indices,
+            Err(_) => continue
+        };
+        let value: f64 = value.parse().map_err(|_| SolutionError::Parse(line.to_string()))?;
+        if value >= 0.5 {
+            chosen.push((indices, line.to_string()));
+        }
+    }
+    recipe_from_indices(n, &chosen)
+}
+
+fn xml_attribute<'a>(line: &'a str, attribute: &str) -> Option<&'a str> {
+    let key = format!("{}=\"", attribute);
+    let start = line.find(&key)? + key.len();
+    let end = line[start..].find('"')? + start;
+    Some(&line[start..end])
+}
+
+pub fn load_solution(n: usize, directory: &String, file_name: &String) -> Result<Recipe, SolutionError> {
+    let contents = utils::read_file(directory, &format!("{}.sol", file_name))
+      .map_err(|e| SolutionError::Read(e.to_string()))?;
+    parse_solution(n, &contents)
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::fmt;
 use std::iter::Iterator;
 
 #[derive(Clone, PartialEq)]
@@ -84,6 +85,13 @@ impl<T: Clone + PartialEq> NdArray<T> {
     }
 }
 
+/// The filled cells as a map from coordinates to values.
+impl<T: Clone + PartialEq + fmt::Debug> fmt::Debug for NdArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
 pub struct NdArrayIter<'a, T> {
     array: &'a NdArray<T>,
     current_index: usize
@@ -0,0 +1,208 @@
+use std::fmt;
+
+use super::*;
+use boxes::{BoxList, BoxListError, PlacedBox};
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+#[derive(Debug, PartialEq)]
+pub enum NpyError {
+    /// The file could not be read.
+    Read(String),
+    /// The file is not an `.npy` file this module reads.
+    Format(String),
+    /// The array does not have the shape of a recipe or a packing.
+    Shape(Vec<usize>),
+    /// The packing in the array could not be traced back to a recipe.
+    Packing(BoxListError)
+}
+
+impl fmt::Display for NpyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NpyError::Read(error) => write!(f, "Could not read npy file: {}", error),
+            NpyError::Format(reason) => write!(f, "Not a readable npy file: {}", reason),
+            NpyError::Shape(shape) => write!(f, "An array of shape {:?} is neither a recipe nor a packing.", shape),
+            NpyError::Packing(error) => write!(f, "Not a packing: {}", error)
+        }
+    }
+}
+
+/// An integer array in C order, as stored in a NumPy `.npy` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NpyArray {
+    pub shape: Vec<usize>,
+    pub data: Vec<i64>
+}
+
+impl NpyArray {
+    pub fn new(shape: Vec<usize>, data: Vec<i64>) -> NpyArray {
+        assert_eq!(shape.iter().product::<usize>(), data.len(), "Data does not fill the shape.");
+        NpyArray { shape, data }
+    }
+
+    /// Version 1.0 of the format with little-endian 64-bit integers, which
+    /// NumPy loads as `int64`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let shape = match self.shape.as_slice() {
+            [length] => format!("({},)", length),
+            shape => format!("({})", shape.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "))
+        };
+        let mut header = format!("{{'descr': '<i8', 'fortran_order': False, 'shape': {}, }}", shape);
+        // The header ends in a newline and is padded so that the data is aligned to 64 bytes.
+        let length = MAGIC.len() + 4 + header.len() + 1;
+        header.push_str(&" ".repeat((64 - length % 64) % 64));
+        header.push('\n');
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for value in &self.data {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reads any version of the format holding signed or unsigned integers
+    /// of 1, 2, 4 or 8 bytes in C order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<NpyArray, NpyError> {
+        let error = |reason: &str| NpyError::Format(reason.to_string());
+        if bytes.len() < MAGIC.len() + 4 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(error("missing magic string"))
+        }
+        let (header_length, begin) = match bytes[MAGIC.len()] {
+            1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+            2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12),
+            _ => return Err(error("unknown version"))
+        };
+        let header = bytes.get(begin..begin + header_length)
+            .and_then(|header| std::str::from_utf8(header).ok())
+            .ok_or_else(|| error("truncated header"))?;
+        let descr = header_value(header, "descr").ok_or_else(|| error("missing descr"))?;
+        if header_value(header, "fortran_order") != Some("False") {
+            return Err(error("only C order is supported"))
+        }
+        let shape: Vec<usize> = header_value(header, "shape").ok_or_else(|| error("missing shape"))?
+            .trim_matches(|c| c == '(' || c == ')').split(',')
+            .map(|v| v.trim()).filter(|v| !v.is_empty())
+            .map(|v| v.parse().map_err(|_| error("invalid shape")))
+            .collect::<Result<_, _>>()?;
+
+        let descr = descr.trim_matches('\'');
+        if descr.len() < 3 {
+            return Err(error(&format!("unsupported dtype {}", descr)))
+        }
+        let (endian, kind, size) = (&descr[..1], &descr[1..2], descr[2..].parse::<usize>().unwrap_or(0));
+        if endian == ">" && size > 1 || !(kind == "i" || kind == "u") || ![1, 2, 4, 8].contains(&size) {
+            return Err(error(&format!("unsupported dtype {}", descr)))
+        }
+        let data_bytes = &bytes[begin + header_length..];
+        let count: usize = shape.iter().product();
+        if data_bytes.len() < count * size {
+            return Err(error("truncated data"))
+        }
+        let data = data_bytes.chunks(size).take(count).map(|chunk| {
+            let mut word = [0u8; 8];
+            word[..size].copy_from_slice(chunk);
+            // Sign extend by shifting the value to the top of the word and back.
+            let shift = 64 - 8 * size as u32;
+            let value = u64::from_le_bytes(word) << shift;
+            if kind == "i" { (value as i64) >> shift } else { (value >> shift) as i64 }
+        }).collect();
+        Ok(NpyArray { shape, data })
+    }
+
+    pub fn save(&self, directory: &String, file_name: &String) {
+        utils::write_bytes(&self.to_bytes(), &format!("exports/{}", directory), &format!("{}.npy", file_name))
+          .expect("Error writing array to file");
+    }
+
+    pub fn load(directory: &String, file_name: &String) -> Result<NpyArray, NpyError> {
+        let bytes = utils::read_bytes(directory, &format!("{}.npy", file_name))
+          .map_err(|e| NpyError::Read(e.to_string()))?;
+        NpyArray::from_bytes(&bytes)
+    }
+}
+
+/// The value of a key in the Python dict literal of a header, as written.
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let begin = header.find(&format!("'{}'", key))? + key.len() + 2;
+    let rest = header[begin..].trim_start().strip_prefix(':')?.trim_start();
+    let end = if rest.starts_with('(') { rest.find(')')? + 1 } else { rest.find(',')? };
+    Some(rest[..end].trim())
+}
+
+impl NpyArray {
+    /// An `n^m × m` array with the orientation of every cell in the order of
+    /// `NdArray::coords`, and `-1` in the rows of empty cells.
+    pub fn from_recipe(recipe: &Recipe) -> NpyArray {
+        let data = recipe.map.coords().iter().flat_map(|coord| {
+            match recipe.map.get(coord) {
+                Some(orientation) => orientation.iter().map(|&side| side as i64).collect(),
+                None => vec!(-1; recipe.m)
+            }
+        }).collect();
+        NpyArray::new(vec!(recipe.map.coords().len(), recipe.m), data)
+    }
+
+    /// An `n^m × m × 2` array with the begin and end along every axis of the
+    /// brick of every cell in the order of `NdArray::coords`, and `-1` for
+    /// both ends along every axis of an empty cell.
+    pub fn from_packing(recipe_builder: &RecipeBuilder, index: usize) -> NpyArray {
+        let packing = recipe_builder.get_packing(index);
+        let m = recipe_builder.get_recipe().m;
+        let data = packing.coords().iter().flat_map(|coord| match packing.get(coord) {
+            Some(hyper_rectangle) => hyper_rectangle.iter().flat_map(|interval| vec!(interval.begin as i64, interval.end as i64)).collect(),
+            None => vec!(-1; 2 * m)
+        }).collect();
+        NpyArray::new(vec!(packing.coords().len(), m, 2), data)
+    }
+
+    /// Reads an array of orientations as written by `from_recipe`, or an
+    /// array of interval endpoints as written by `from_packing`, in which
+    /// case the recipe is inferred from the positions of the bricks.
+    pub fn to_recipe(&self) -> Result<Recipe, NpyError> {
+        let shape_error = || NpyError::Shape(self.shape.clone());
+        let (cells, m) = match self.shape.as_slice() {
+            &[cells, m] | &[cells, m, 2] if cells > 0 && m > 0 => (cells, m),
+            _ => return Err(shape_error())
+        };
+        let n = (1..=cells).find(|n| n.checked_pow(m as u32).is_none_or(|count| count >= cells)).unwrap_or(0);
+        if n.checked_pow(m as u32) != Some(cells) {
+            return Err(shape_error())
+        }
+        if self.shape.len() == 3 {
+            return self.packing_to_recipe(n, m)
+        }
+        let mut recipe = Recipe::new(n, m);
+        for (coord, row) in recipe.map.coords().clone().iter().zip(self.data.chunks(m)) {
+            if row.iter().all(|&side| side == -1) { continue }
+            if row.iter().any(|&side| side < 0 || side as usize >= n) {
+                return Err(NpyError::Format(format!("{:?} is not an orientation", row)))
+            }
+            recipe.map.insert(coord, row.iter().map(|&side| side as usize).collect());
+        }
+        Ok(recipe)
+    }
+
+    fn packing_to_recipe(&self, n: usize, m: usize) -> Result<Recipe, NpyError> {
+        // The bricks of a partial packing cannot be traced back to cells.
+        let bricks = self.data.chunks(2 * m).filter(|row| !row.iter().all(|&bound| bound == -1)).count();
+        if bricks < self.shape[0] {
+            return Err(NpyError::Packing(BoxListError::Count(bricks)))
+        }
+        let boxes: Vec<PlacedBox> = self.data.chunks(2 * m).map(|row| PlacedBox {
+            coord: Vec::new(),
+            orientation: Vec::new(),
+            hyper_rectangle: row.chunks(2).map(|bounds| {
+                Interval { begin: bounds[0] as IntType, end: bounds[1] as IntType }
+            }).collect()
+        }).collect();
+        // Every line of a packing has a brick of each side, so the first line
+        // along the first axis gives the dimension tuple.
+        let stride = n.pow(m as u32 - 1);
+        let mut dimension_tuple: Vec<IntType> = (0..n).map(|i| boxes[i * stride].hyper_rectangle[0].width()).collect();
+        dimension_tuple.sort();
+        BoxList { dimension_tuple, boxes }.infer_recipe().map_err(NpyError::Packing)
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{Add, Sub, Mul};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Zero, ToPrimitive};
+
+/// The side lengths a packing can be built from.
+///
+/// Only exact types qualify, since validity hinges on comparing sums of sides.
+pub trait Number: Clone + fmt::Debug + fmt::Display + Eq + Ord + Hash + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    fn zero() -> Self;
+
+    /// An approximation used for drawing.
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! primitive_number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                fn zero() -> $t { 0 }
+
+                fn to_f64(&self) -> f64 { *self as f64 }
+            }
+        )*
+    }
+}
+
+primitive_number!(i32, i64, i128);
+
+impl Number for BigInt {
+    fn zero() -> BigInt { Zero::zero() }
+
+    fn to_f64(&self) -> f64 { ToPrimitive::to_f64(self).unwrap_or(f64::NAN) }
+}
+
+impl Number for BigRational {
+    fn zero() -> BigRational { Zero::zero() }
+
+    fn to_f64(&self) -> f64 { ToPrimitive::to_f64(self).unwrap_or(f64::NAN) }
+}
+
+pub fn sum<T: Number>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |acc, v| acc + v.clone())
+}
@@ -4,13 +4,13 @@ use std::error::Error;
 use super::*;
 use combinatorics::*;
 
-const COLORS: &'static [&'static str] = &[
-    "rgb(236, 31, 38)",  // Red
-    "rgb(121, 193, 68)", // Green
-    "rgb(0, 125, 199)",  // Blue
-    "rgb(244, 112, 37)", // Orange
-    "rgb(252, 223, 7)",  // Yellow
-    "rgb(138, 40, 143)", // Violet
+const COLORS: &[(u8, u8, u8)] = &[
+    (236, 31, 38),  // Red
+    (121, 193, 68), // Green
+    (0, 125, 199),  // Blue
+    (244, 112, 37), // Orange
+    (252, 223, 7),  // Yellow
+    (138, 40, 143), // Violet
 ];
 const TIKZ_COLORS: &'static [&'static str] = &[
     "custom-red",  // Red
@@ -23,53 +23,108 @@ const TIKZ_COLORS: &'static [&'static str] = &[
 const MARGIN: f64 = 40.0;
 const WIDTH: f64 = 800.0;
 
+/// `COLORS` first and then further hues spread by the golden angle, so that
+/// any number of bricks can be told apart.
+pub fn rgb_palette(count: usize) -> Vec<(u8, u8, u8)> {
+    (0..count).map(|i| {
+        if i < COLORS.len() {
+            return COLORS[i]
+        }
+        hsl_to_rgb(((i - COLORS.len()) as f64 * 137.508 + 15.0) % 360.0, 0.65, 0.5)
+    }).collect()
+}
+
+/// Pairs of an svg and a tikz colour for `count` bricks, using the custom
+/// tikz colours of `COLORS` where there is one.
+pub fn palette(count: usize) -> Vec<(String, String)> {
+    rgb_palette(count).into_iter().enumerate().map(|(i, (r, g, b))| {
+        let tikz = match TIKZ_COLORS.get(i) {
+            Some(name) => name.to_string(),
+            None => tikz_rgb((r, g, b))
+        };
+        (format!("rgb({}, {}, {})", r, g, b), tikz)
+    }).collect()
+}
+
+fn tikz_rgb((r, g, b): (u8, u8, u8)) -> String {
+    format!("{{rgb,255:red,{};green,{};blue,{}}}", r, g, b)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (hue / 60.0) as usize {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x)
+    };
+    let scale = |v: f64| ((v + lightness - chroma / 2.0) * 255.0).round() as u8;
+    (scale(r), scale(g), scale(b))
+}
+
 #[derive(Clone)]
-pub struct Rectangle {
-    pub x: IntType,
-    pub y: IntType,
-    pub width: IntType,
-    pub height: IntType
+pub struct Rectangle<T = IntType> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T
 }
 
 #[derive(Clone)]
-pub struct Plot {
+pub struct Plot<T = IntType> {
     pub name: Option<String>,
-    pub rectangles: Vec<Rectangle>
+    pub rectangles: Vec<Rectangle<T>>
 }
 
-pub struct Figure {
+/// Coordinates are written as `f64`, so rational and big sides are drawn approximately.
+pub struct Figure<T = IntType> {
     pub name: Option<String>,
-    pub plots: Vec<Plot>,
-    pub dimension_tuple: Vec<IntType>,
+    pub plots: Vec<Plot<T>>,
+    pub dimension_tuple: Vec<T>,
     pub rows: usize,
     pub columns: usize
 }
 
-impl Rectangle {
-    fn to_svg(&self, colors: &HashMap<IntType, usize>) -> String {
-        if self.width == 0 || self.height == 0 { return format!("") }
+impl<T: Number> Rectangle<T> {
+    fn to_svg(&self, colors: &HashMap<T, String>) -> String {
+        if self.width == T::zero() && self.height == T::zero() { return format!("") }
+        if self.width == T::zero() || self.height == T::zero() {
+            // A brick with a zero side is drawn as a segment.
+            return format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" style=\"stroke:{};stroke-width:3;\" vector-effect=\"non-scaling-stroke\" />",
+            self.x.to_f64(), self.y.to_f64(), (self.x.clone() + self.width.clone()).to_f64(), (self.y.clone() + self.height.clone()).to_f64(),
+            colors[&(self.width.clone() + self.height.clone())])
+        }
         format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" style=\"fill:{};stroke:#000;stroke-width:1;\" vector-effect=\"non-scaling-stroke\" />",
-        self.x, self.y, self.width, self.height, COLORS[colors[&(self.width + self.height)]])
+        self.x.to_f64(), self.y.to_f64(), self.width.to_f64(), self.height.to_f64(), colors[&(self.width.clone() + self.height.clone())])
     }
 
-    fn to_tikz(&self, colors: &HashMap<IntType, usize>) -> String {
-        if self.width == 0 || self.height == 0 { return format!("") }
+    fn to_tikz(&self, colors: &HashMap<T, String>) -> String {
+        if self.width == T::zero() && self.height == T::zero() { return format!("") }
+        if self.width == T::zero() || self.height == T::zero() {
+            return format!("\\draw[{}, very thick] ({},{}) -- ({},{});",
+            colors[&(self.width.clone() + self.height.clone())], self.x.to_f64(), self.y.to_f64(),
+            (self.x.clone() + self.width.clone()).to_f64(), (self.y.clone() + self.height.clone()).to_f64())
+        }
         format!("\\filldraw[fill={}, draw=black] ({},{}) rectangle ({},{});",
-        TIKZ_COLORS[colors[&(self.width + self.height)]], self.x, self.y, self.x + self.width, self.y + self.height)
+        colors[&(self.width.clone() + self.height.clone())], self.x.to_f64(), self.y.to_f64(),
+        (self.x.clone() + self.width.clone()).to_f64(), (self.y.clone() + self.height.clone()).to_f64())
     }
 }
 
-impl Plot {
-    fn to_svg(&self, colors: &HashMap<IntType, usize>) -> String {
+impl<T: Number> Plot<T> {
+    fn to_svg(&self, colors: &HashMap<T, String>) -> String {
         self.rectangles.iter().map(|rect| rect.to_svg(colors)).collect::<Vec<String>>().join("\n")
     }
 
-    fn to_tikz(&self, colors: &HashMap<IntType, usize>) -> String {
+    fn to_tikz(&self, colors: &HashMap<T, String>) -> String {
         self.rectangles.iter().map(|rect| rect.to_tikz(colors)).collect::<Vec<String>>().join("\n")
     }
 }
 
-impl Figure {
+impl<T: Number> Figure<T> {
     pub fn save_svg(&self, directory: &String, file_name: &String) {
         match utils::write_file(&self.to_svg(), &format!("plots/{}", directory), &format!("{}.svg", file_name)) {
             Err(why) => panic!("Error saving svg: {}", why.description()),
@@ -84,16 +139,24 @@ impl Figure {
         }
     }
 
-    fn to_svg(&self) -> String {
+    /// Bricks are coloured by the sum of their two visible sides.
+    fn colors(&self, tikz: bool) -> HashMap<T, String> {
+        let pairs = combinations(&self.dimension_tuple, 2);
+        let palette = palette(pairs.len());
+        let mut colors: HashMap<T, String> = HashMap::new();
+        for (pair, (svg_color, tikz_color)) in pairs.iter().zip(palette) {
+            colors.insert(number::sum(pair), if tikz { tikz_color } else { svg_color });
+        }
+        colors
+    }
+
+    pub fn to_svg(&self) -> String {
         assert!(self.plots.len() == self.rows * self.columns, "Number of plots doesn't match number of rows and columns.");
 
-        let mut colors: HashMap<IntType, usize> = HashMap::new();
-        for (i, sum) in combinations(&self.dimension_tuple, 2).iter().map(|li| li.iter().sum()).enumerate() {
-            colors.insert(sum, i);
-        }
+        let colors = self.colors(false);
 
         let mut svg = String::new();
-        let dimension_tuple_sum = self.dimension_tuple.iter().sum::<IntType>() as f64;
+        let dimension_tuple_sum = number::sum(&self.dimension_tuple).to_f64();
         let plot_size = (WIDTH - MARGIN * ((self.columns + 1) as f64)) / self.columns as f64;
         let plot_scale = plot_size / dimension_tuple_sum;
         let figure_height = MARGIN * (self.rows + 1) as f64 + plot_size * self.rows as f64;
@@ -116,19 +179,16 @@ impl Figure {
         svg
     }
 
-    fn to_tikz(&self) -> String {
+    pub fn to_tikz(&self) -> String {
         assert!(self.plots.len() == self.rows * self.columns, "Number of plots doesn't match number of rows and columns.");
 
-        let mut colors: HashMap<IntType, usize> = HashMap::new();
-        for (i, sum) in combinations(&self.dimension_tuple, 2).iter().map(|li| li.iter().sum()).enumerate() {
-            colors.insert(sum, i);
-        }
+        let colors = self.colors(true);
 
         let mut tikz = String::new();
-        let dimension_tuple_sum: IntType = self.dimension_tuple.iter().sum();
+        let dimension_tuple_sum = number::sum(&self.dimension_tuple).to_f64();
         let figure_scale = 1.0 / (self.columns as f64) - 0.03;
         let text_width = 12.0;
-        let tikz_scale = figure_scale * text_width / dimension_tuple_sum as f64;
+        let tikz_scale = figure_scale * text_width / dimension_tuple_sum;
 
         let rows = (0..self.rows).map(|row|
             (0..self.columns).map(|column| {
@@ -155,105 +215,279 @@ impl Figure {
     }
 }
 
-pub fn plot_3d(recipe: &Recipe, dimension_tuple: &DimensionTuple, name: &String) {
-    const N: usize = 3;
-    let recipe_builder = RecipeBuilder::generate(recipe, vec!(dimension_tuple.clone()));
+/// How `plot_slices` lays out a figure.
+#[derive(Clone, Debug, Default)]
+pub struct SliceOptions {
+    /// The axes drawn across and down every plot, or every pair of axes if `None`.
+    pub axes: Option<(usize, usize)>,
+    /// Names of the axes, by default `x`, `y`, `z` and `w`, or `x1`, `x2`, .. beyond four axes.
+    pub labels: Option<Vec<String>>,
+    /// Plots per row, by default `n` so that a row runs through the levels of the last fixed axis.
+    pub columns: Option<usize>
+}
 
-    let dim_labels = ["x", "y", "z"];
+/// Draws the two-dimensional slices of the packing of a recipe, one plot for
+/// every level of the other axes and every pair of axes drawn. Pairs are
+/// taken in the order of the combinations of fixed axes.
+pub fn plot_slices<T: Number>(recipe: &Recipe, dimension_tuple: &[T], options: &SliceOptions) -> Figure<T> {
+    let (n, m) = (recipe.n, recipe.m);
+    assert!(m >= 2, "Can only plot slices of at least two dimensions.");
+    let recipe_builder = RecipeBuilder::generate_with_sides(recipe, vec!(dimension_tuple.to_vec()));
+    let labels = options.labels.clone().unwrap_or_else(|| {
+        if m <= 4 {
+            ["x", "y", "z", "w"][..m].iter().map(|label| label.to_string()).collect()
+        } else {
+            (1..=m).map(|d| format!("x{}", d)).collect()
+        }
+    });
+    let dims: Vec<usize> = (0..m).collect();
+    let pairs: Vec<(usize, usize)> = match options.axes {
+        Some(axes) => vec!(axes),
+        None => combinations(&dims, m - 2).iter().map(|fixed| {
+            let varying = utils::list_except(&dims, fixed);
+            (varying[0], varying[1])
+        }).collect()
+    };
     let mut plots = Vec::new();
-    for dim in 0..N {
-        for level in 0..N {
-            let rects = recipe_builder.get_rectangles_at(vec!((dim, level)));
-            let square_name = utils::list_except(&dim_labels, &[dim_labels[dim]]).join("");
-            let plot_name = format!("{}-square at {}={}", square_name, dim_labels[dim], level);
-            let plot = plot::Plot {
-                name: Some(plot_name),
-                rectangles: rects
+    for &(across, down) in &pairs {
+        assert!(across != down && across < m && down < m, "Invalid pair of axes.");
+        let fixed_dims: Vec<usize> = dims.iter().cloned().filter(|&d| d != across && d != down).collect();
+        for levels in utils::make_coords(&vec!(n; m - 2)) {
+            let fixed: Vec<(usize, usize)> = fixed_dims.iter().cloned().zip(levels).collect();
+            let at: Vec<String> = fixed.iter().map(|&(dim, level)| format!("{}={}", labels[dim], level + 1)).collect();
+            let name = if at.is_empty() {
+                format!("{}{}-square", labels[across], labels[down])
+            } else {
+                format!("{}{}-square at {}", labels[across], labels[down], at.join(", "))
             };
-            plots.push(plot);
+            plots.push(Plot {
+                name: Some(name),
+                rectangles: recipe_builder.get_slice((across, down), &fixed)
+            });
         }
     }
-    let figure = plot::Figure {
+    let columns = options.columns.unwrap_or(if m > 2 { n } else { 1 });
+    let rows = plots.len().div_ceil(columns);
+    plots.resize(rows * columns, Plot { name: None, rectangles: Vec::new() });
+    Figure {
         name: None,
-        plots: plots,
-        dimension_tuple: dimension_tuple.clone(),
-        rows: N,
-        columns: N
-    };
+        plots,
+        dimension_tuple: dimension_tuple.to_vec(),
+        rows,
+        columns
+    }
+}
+
+pub fn plot_3d<T: Number>(recipe: &Recipe, dimension_tuple: &[T], name: &String) {
+    let figure = plot_slices(recipe, dimension_tuple, &SliceOptions::default());
     figure.save_svg(&String::from("cubes"), name);
     figure.save_tikz(&String::from("cubes"), name);
 }
 
-pub fn plot_4d(recipe: &Recipe, dimension_tuple: &DimensionTuple, name: &String) {
-    const N: usize = 4;
-    const M: usize = 4;
-    let recipe_builder = RecipeBuilder::generate(recipe, vec!(dimension_tuple.clone()));
+pub fn plot_4d<T: Number>(recipe: &Recipe, dimension_tuple: &[T], name: &String) {
+    let figure = plot_slices(recipe, dimension_tuple, &SliceOptions::default());
+    figure.save_svg(&String::from("tesseracts"), name);
+    figure.save_tikz(&String::from("tesseracts"), name);
+}
 
-    let dim_labels = ["x", "y", "z", "w"];
-    let dims: Vec<usize> = (0..M).collect();
-    let fixed_dims = combinatorics::combinations(&dims, M - 2);
-    let mut plots = Vec::new();
-    for fixed in &fixed_dims {
-        for level0 in 0..N {
-            for level1 in 0..N {
-                let rects = recipe_builder.get_rectangles_at(vec!(
-                    (fixed[0], level0),
-                    (fixed[1], level1)
-                ));
-                let plot_name = format!("${} = {}$ and ${} = {}$.", dim_labels[fixed[0]], level0 + 1, dim_labels[fixed[1]], level1 + 1);
-                let plot = plot::Plot {
-                    name: Some(plot_name),
-                    rectangles: rects
-                };
-                plots.push(plot);
-            }
+pub fn plot_4d_cube<T: Number>(recipe: &Recipe, dimension_tuple: &[T], name: &String) {
+    plot_3d(recipe, dimension_tuple, name);
+}
+
+/// How `render_isometric` draws a packing.
+#[derive(Clone, Debug)]
+pub struct IsometricOptions {
+    /// The axes drawn, the last one pointing up.
+    pub axes: [usize; 3],
+    /// Levels of the axes not drawn, which are at level 0 unless given.
+    pub levels: Vec<(usize, usize)>,
+    /// Gap between neighbouring bricks as a fraction of the largest side, for an exploded view.
+    pub explode: f64,
+    /// Direction of the viewer in degrees, around the up axis from the first axis.
+    pub azimuth: f64,
+    /// Direction of the viewer in degrees, above the plane of the first two axes.
+    pub elevation: f64
+}
+
+impl Default for IsometricOptions {
+    /// The first three axes seen along the diagonal, which is the isometric projection.
+    fn default() -> IsometricOptions {
+        IsometricOptions {
+            axes: [0, 1, 2],
+            levels: Vec::new(),
+            explode: 0.0,
+            azimuth: 45.0,
+            elevation: (0.5f64).sqrt().atan().to_degrees()
         }
     }
-    let figure = plot::Figure {
-        name: None,
-        plots: plots,
-        dimension_tuple: dimension_tuple.clone(),
-        rows: 24,
-        columns: N
-    };
-    figure.save_svg(&String::from("tesseracts"), name);
-    figure.save_tikz(&String::from("tesseracts"), name);
 }
 
-pub fn plot_4d_cube(recipe: &Recipe, dimension_tuple: &DimensionTuple, name: &String) {
-    const N: usize = 4;
-    const M: usize = 3;
-    let recipe_builder = RecipeBuilder::generate(recipe, vec!(dimension_tuple.clone()));
+/// Bounds of a brick along the three drawn axes.
+type Bounds = [(f64, f64); 3];
 
-    let dim_labels = ["x", "y", "z"];
-    let dims = (0..M).collect::<Vec<usize>>();
-    let fixed_dims = combinatorics::combinations(&dims, M - 2);
-    let mut plots = Vec::new();
+/// A face of a brick projected onto the drawing, with `y` pointing up.
+#[derive(Clone, Debug)]
+pub struct Face {
+    pub points: Vec<(f64, f64)>,
+    pub color: (u8, u8, u8)
+}
 
-    for fixed in &fixed_dims {
-        let dim = fixed[0];
-        for level in 0..N {
-            let rects = recipe_builder.get_rectangles_at(vec!(
-                (dim, level)
-            ));
+/// The visible faces of the bricks, back to front.
+#[derive(Clone, Debug)]
+pub struct IsometricDrawing {
+    pub faces: Vec<Face>
+}
 
-            let square_name = utils::list_except(&dim_labels, &[dim_labels[dim]]).join("");
-            let plot_name = format!("{}-square at {}={}", square_name, dim_labels[dim], level + 1);
+/// Draws the bricks of a three-dimensional packing, or of a three-dimensional
+/// slice of a larger one, in parallel projection.
+///
+/// Bricks are coloured by the sides along the drawn axes, and their faces
+/// shaded by the direction they face. Two bricks of a packing are separated
+/// along some axis, and the one on the viewer's side of it is drawn later.
+pub fn render_isometric<T: Number>(recipe: &Recipe, dimension_tuple: &[T], options: &IsometricOptions) -> IsometricDrawing {
+    let (n, m) = (recipe.n, recipe.m);
+    assert!(options.axes.iter().all(|&axis| axis < m) && options.axes[0] != options.axes[1]
+        && options.axes[1] != options.axes[2] && options.axes[0] != options.axes[2], "Invalid axes.");
+    let recipe_builder = RecipeBuilder::generate_with_sides(recipe, vec!(dimension_tuple.to_vec()));
+    let packing = recipe_builder.get_packing(0);
+    let gap = options.explode * dimension_tuple.iter().map(|side| side.to_f64()).fold(0.0, f64::max);
 
-            let plot = plot::Plot {
-                name: Some(plot_name),
-                rectangles: rects
-            };
-            plots.push(plot);
+    let (azimuth, elevation) = (options.azimuth.to_radians(), options.elevation.to_radians());
+    let view = [elevation.cos() * azimuth.cos(), elevation.cos() * azimuth.sin(), elevation.sin()];
+    let project = |p: [f64; 3]| -> (f64, f64) {
+        (-azimuth.sin() * p[0] + azimuth.cos() * p[1],
+         -elevation.sin() * (azimuth.cos() * p[0] + azimuth.sin() * p[1]) + elevation.cos() * p[2])
+    };
+
+    // The bricks in the slice as bounds along the drawn axes.
+    let colors = rgb_palette((0..3).map(|i| n - i).product());
+    let mut bricks: Vec<(Bounds, (u8, u8, u8))> = Vec::new();
+    for coord in &recipe_builder.slice_coords(&options.axes, &options.levels) {
+        let hyper_rectangle = packing.get(coord).unwrap();
+        let mut bounds = [(0.0, 0.0); 3];
+        for (i, &axis) in options.axes.iter().enumerate() {
+            let offset = gap * coord[axis] as f64;
+            bounds[i] = (hyper_rectangle[axis].begin.to_f64() + offset, hyper_rectangle[axis].end.to_f64() + offset);
         }
+        let orientation = recipe.map.get(coord).unwrap();
+        let sides: Vec<usize> = options.axes.iter().map(|&axis| orientation[axis]).collect();
+        bricks.push((bounds, colors[binary::orientation_rank(&sides, n) as usize]));
     }
-    let figure = plot::Figure {
-        name: None,
-        plots: plots,
-        dimension_tuple: dimension_tuple.clone(),
-        rows: 3,
-        columns: N
+
+    // Painter's order: a brick is drawn after those behind it that it may cover.
+    let outline = |bounds: &Bounds| -> (f64, f64, f64, f64) {
+        let corners: Vec<(f64, f64)> = (0..8).map(|k| {
+            project([0, 1, 2].map(|i| if k >> i & 1 == 0 { bounds[i].0 } else { bounds[i].1 }))
+        }).collect();
+        corners.iter().fold((f64::MAX, f64::MAX, f64::MIN, f64::MIN), |(x0, y0, x1, y1), &(x, y)| {
+            (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+        })
     };
-    figure.save_svg(&String::from("cubes"), name);
-    figure.save_tikz(&String::from("cubes"), name);
+    let outlines: Vec<(f64, f64, f64, f64)> = bricks.iter().map(|(bounds, _)| outline(bounds)).collect();
+    let count = bricks.len();
+    let mut behind: Vec<Vec<usize>> = vec!(Vec::new(); count);
+    for a in 0..count {
+        for b in a + 1..count {
+            let (oa, ob) = (outlines[a], outlines[b]);
+            if oa.2 <= ob.0 || ob.2 <= oa.0 || oa.3 <= ob.1 || ob.3 <= oa.1 { continue }
+            let (ba, bb) = (&bricks[a].0, &bricks[b].0);
+            let front = (0..3).find_map(|i| {
+                if ba[i].1 <= bb[i].0 && view[i] != 0.0 { Some(if view[i] > 0.0 { b } else { a }) }
+                else if bb[i].1 <= ba[i].0 && view[i] != 0.0 { Some(if view[i] > 0.0 { a } else { b }) }
+                else { None }
+            });
+            match front {
+                Some(front) if front == a => behind[a].push(b),
+                Some(_) => behind[b].push(a),
+                None => ()
+            }
+        }
+    }
+    let depth = |bounds: &Bounds| (0..3).map(|i| view[i] * (bounds[i].0 + bounds[i].1)).sum::<f64>();
+    let mut remaining: Vec<usize> = behind.iter().map(|list| list.len()).collect();
+    let mut order = Vec::with_capacity(count);
+    let mut drawn = vec!(false; count);
+    while order.len() < count {
+        // Of the bricks with nothing left behind them, the deepest first; a
+        // cycle, which a packing should not have, is broken by depth alone.
+        let next = (0..count).filter(|&a| !drawn[a])
+            .min_by(|&a, &b| (remaining[a] > 0).cmp(&(remaining[b] > 0))
+                .then(depth(&bricks[a].0).partial_cmp(&depth(&bricks[b].0)).unwrap()))
+            .unwrap();
+        drawn[next] = true;
+        order.push(next);
+        for (a, list) in behind.iter().enumerate() {
+            if list.contains(&next) { remaining[a] -= 1; }
+        }
+    }
+
+    let mut faces = Vec::new();
+    for &index in &order {
+        let (bounds, color) = &bricks[index];
+        for axis in 0..3 {
+            if view[axis] == 0.0 { continue }
+            let level = if view[axis] > 0.0 { bounds[axis].1 } else { bounds[axis].0 };
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            let points = [(0, 0), (1, 0), (1, 1), (0, 1)].iter().map(|&(i, j)| {
+                let mut p = [0.0; 3];
+                p[axis] = level;
+                p[u] = if i == 0 { bounds[u].0 } else { bounds[u].1 };
+                p[v] = if j == 0 { bounds[v].0 } else { bounds[v].1 };
+                project(p)
+            }).collect();
+            // Faces pointing up are lit fully and the sides are darker.
+            let shade = [0.8, 0.6, 1.0][axis];
+            let shaded = |c: u8| (c as f64 * shade).round() as u8;
+            faces.push(Face { points, color: (shaded(color.0), shaded(color.1), shaded(color.2)) });
+        }
+    }
+    IsometricDrawing { faces }
+}
+
+impl IsometricDrawing {
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        self.faces.iter().flat_map(|face| face.points.iter())
+            .fold((f64::MAX, f64::MAX, f64::MIN, f64::MIN), |(x0, y0, x1, y1), &(x, y)| {
+                (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+            })
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (x0, y0, x1, y1) = self.bounds();
+        let scale = (WIDTH - 2.0 * MARGIN) / (x1 - x0).max(f64::MIN_POSITIVE);
+        let height = (y1 - y0) * scale + 2.0 * MARGIN;
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n", WIDTH, height, WIDTH, height);
+        for face in &self.faces {
+            let points: Vec<String> = face.points.iter()
+                .map(|&(x, y)| format!("{:.3},{:.3}", MARGIN + (x - x0) * scale, MARGIN + (y1 - y) * scale)).collect();
+            let (r, g, b) = face.color;
+            svg.push_str(&format!("<polygon points=\"{}\" style=\"fill:rgb({}, {}, {});stroke:#000;stroke-width:1;stroke-linejoin:round;\" />\n",
+                points.join(" "), r, g, b));
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    pub fn to_tikz(&self) -> String {
+        let (x0, _, x1, _) = self.bounds();
+        let text_width = 12.0;
+        let scale = text_width / (x1 - x0).max(f64::MIN_POSITIVE);
+        let mut tikz = format!("\\begin{{tikzpicture}}[scale={:.4}, line join=round]\n", scale);
+        for face in &self.faces {
+            let points: Vec<String> = face.points.iter().map(|&(x, y)| format!("({:.3},{:.3})", x, y)).collect();
+            tikz.push_str(&format!("\\filldraw[fill={}, draw=black] {} -- cycle;\n", tikz_rgb(face.color), points.join(" -- ")));
+        }
+        tikz.push_str("\\end{tikzpicture}\n");
+        tikz
+    }
+
+    pub fn save_svg(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_svg(), &format!("plots/{}", directory), &format!("{}.svg", file_name))
+          .expect("Error saving svg");
+    }
+
+    pub fn save_tikz(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_tikz(), &format!("plots/{}", directory), &format!("{}.tikz", file_name))
+          .expect("Error saving tikz");
+    }
 }
@@ -1,4 +1,6 @@
+use std::fmt;
 use std::iter::repeat;
+use std::str::FromStr;
 use serde_json::Value;
 
 use super::*;
@@ -36,7 +38,7 @@ impl Recipe {
                 symmetries.push(self.symmetry(axes, directions));
             }
         }
-        assert!(symmetries.len() == (2 as usize).pow(self.m as u32) * factorial(self.m) "Invalid number of symmetries.");
+        assert!(symmetries.len() == (2 as usize).pow(self.m as u32) * factorial(self.m), "Invalid number of symmetries.");
         symmetries
     }
 
@@ -65,6 +67,32 @@ impl Recipe {
         unique
     }
 
+    /// `find_unique` for tuples with repeated sides, where orientations are
+    /// compared after merging those that give the same brick. Relabelling
+    /// equal sides then maps a recipe to itself, so the larger stabiliser of
+    /// such a tuple is accounted for.
+    pub fn find_unique_for<S: AsRef<[IntType]>>(recipes: Vec<Recipe>, dimension_tuples: &[S]) -> Vec<Recipe> {
+        let classes = side_classes(dimension_tuples);
+        let mut unique: Vec<Recipe> = Vec::new();
+        for suspect_recipe in recipes {
+            let suspect_recipe = suspect_recipe.merged(&classes);
+            let symmetries: Vec<Recipe> = suspect_recipe.symmetries().iter().map(|symmetry| symmetry.merged(&classes)).collect();
+            if unique.iter().all(|recipe| !symmetries.contains(recipe)) {
+                unique.push(suspect_recipe)
+            }
+        }
+        unique
+    }
+
+    /// The recipe with every orientation merged by `merge_orientation`.
+    pub fn merged(&self, classes: &[usize]) -> Recipe {
+        Recipe {
+            n: self.n,
+            m: self.m,
+            map: self.map.map(|(coord, orientation)| (coord.clone(), merge_orientation(orientation, classes)))
+        }
+    }
+
     pub fn distance_to(&self, other: &Recipe) -> usize {
         self.symmetries().iter().map(|symmetry| {
             symmetry.map.differences(&other.map)
@@ -81,10 +109,79 @@ impl Recipe {
         }
     }
 
+    /// The least of the symmetries of the recipe, comparing the orientations in coordinate order.
+    pub fn canonical(&self) -> Recipe {
+        self.symmetries().into_iter().min_by_key(|recipe| recipe.orientations()).unwrap()
+    }
+
+    /// 64-bit FNV-1a hash of the canonical recipe, shared by all its symmetries.
+    pub fn canonical_hash(&self) -> String {
+        let canonical = self.canonical();
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut feed = |byte: u8| {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        };
+        feed(canonical.n as u8);
+        feed(canonical.m as u8);
+        for orientation in canonical.orientations() {
+            match orientation {
+                Some(orientation) => orientation.iter().for_each(|&side| feed(side as u8)),
+                None => feed(0xff)
+            }
+        }
+        format!("{:016x}", hash)
+    }
+
+    fn orientations(&self) -> Vec<Option<Orientation>> {
+        self.map.coords().iter().map(|coord| self.map.get(coord).cloned()).collect()
+    }
+
+    /// Whether the recipe is valid for every tuple with unique sums satisfying Hoffman's inequality.
+    pub fn is_universal(&self) -> bool {
+        RecipeBuilder::generate(self, vec!()).validity_conditions().iter().all(|condition| {
+            !symbolic::has_hoffman_counterexample(&condition.alternatives)
+        })
+    }
+
+    /// The region of tuples around `dimension_tuple` in which the recipe stays valid,
+    /// or the colliding bricks if it is not valid there.
+    pub fn validity_region(&self, dimension_tuple: &[IntType]) -> Result<region::ValidityRegion, Vec<sweep::Violation>> {
+        region::ValidityRegion::new(self, dimension_tuple)
+    }
+}
+
+/// For each side index, the least index whose side is equal to it in every tuple.
+pub fn side_classes<T: PartialEq, S: AsRef<[T]>>(dimension_tuples: &[S]) -> Vec<usize> {
+    let n = dimension_tuples.first().map(|sides| sides.as_ref().len()).unwrap_or(0);
+    (0..n).map(|i| {
+        (0..i).find(|&j| dimension_tuples.iter().all(|sides| sides.as_ref()[j] == sides.as_ref()[i])).unwrap_or(i)
+    }).collect()
+}
+
+/// The orientation giving the same brick that uses the least indices of each
+/// class of equal sides, in order along the axes.
+pub fn merge_orientation(orientation: &[usize], classes: &[usize]) -> Orientation {
+    let mut used = vec!(false; classes.len());
+    orientation.iter().map(|&side| {
+        let merged = (0..classes.len()).find(|&i| classes[i] == classes[side] && !used[i]).unwrap();
+        used[merged] = true;
+        merged
+    }).collect()
+}
+
+/// The orientation in the grid notation of `Recipe`'s `Display`.
+pub fn orientation_word(orientation: &[usize]) -> String {
+    orientation.iter().map(|&side| (b'a' + side as u8) as char).collect()
 }
 
 impl Recipe {
     pub fn save_json(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_json(), &format!("exports/{}", directory), &format!("{}.json", file_name))
+          .expect("Error writing recipe to file");
+    }
+
+    pub fn to_json(&self) -> String {
         let n = self.n.to_string();
         let m = self.m.to_string();
         let map = format!("[{}]", self.map.iter()
@@ -93,15 +190,25 @@ impl Recipe {
             let orientation = format!("[{}]", orientation.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "));
             format!("{{ \"coord\": {}, \"permutation\": {} }}", coord, orientation)
         }).collect::<Vec<_>>().join(",\n"));
-        let json = format!("{{ \"n\": {}, \"m\": {}, \"map\": {} }}", n, m, map);
-        utils::write_file(&json, &format!("exports/{}", directory), &format!("{}.json", file_name))
-          .expect("Error writing recipe to file");
+        format!("{{ \"n\": {}, \"m\": {}, \"map\": {} }}", n, m, map)
+    }
+
+    pub fn save_npy(&self, directory: &String, file_name: &String) {
+        npy::NpyArray::from_recipe(self).save(directory, file_name);
+    }
+
+    pub fn load_npy(directory: &String, file_name: &String) -> Result<Recipe, npy::NpyError> {
+        npy::NpyArray::load(directory, file_name)?.to_recipe()
     }
 
     pub fn load_json(directory: &String, file_name: &String) -> Recipe {
         let json = utils::read_file(directory, &format!("{}.json", file_name))
           .expect("Error reading recipe from file.");
-        let recipe_json: Value = serde_json::from_str(&json)
+        Recipe::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> Recipe {
+        let recipe_json: Value = serde_json::from_str(json)
           .expect("Error reading recipe from file.");
 
         let n = recipe_json["n"].as_u64().unwrap() as usize;
@@ -123,3 +230,86 @@ impl Recipe {
         }
     }
 }
+
+#[derive(Debug, PartialEq)]
+pub enum ParseRecipeError {
+    /// A cell is not an orientation of letters `a`, `b`, .. or a run of dots.
+    Word(String),
+    /// The cells do not form `n^m` rows of `n` cells in slices of `n` rows.
+    Shape(String)
+}
+
+impl fmt::Display for ParseRecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRecipeError::Word(word) => write!(f, "Not an orientation: {}", word),
+            ParseRecipeError::Shape(reason) => write!(f, "Not a grid of orientations: {}", reason)
+        }
+    }
+}
+
+/// The grid notation: every orientation is a word of letters, `a` for side
+/// 0, `b` for side 1 and so on, so that `cab` places side 2 along the first
+/// axis. An empty cell is `m` dots. The last axis runs along a row and the
+/// one before it down the rows, and the slices for the remaining axes follow
+/// each other in coordinate order, separated by blank lines.
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let words: Vec<String> = self.map.coords().iter().map(|coord| match self.map.get(coord) {
+            Some(orientation) => orientation_word(orientation),
+            None => ".".repeat(self.m)
+        }).collect();
+        let rows: Vec<String> = words.chunks(self.n.max(1)).map(|row| row.join(" ")).collect();
+        let slices: Vec<String> = rows.chunks(if self.m < 2 { 1 } else { self.n.max(1) }).map(|slice| slice.join("\n")).collect();
+        write!(f, "{}", slices.join("\n\n"))
+    }
+}
+
+impl fmt::Debug for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Recipe (n = {}, m = {}):\n{}", self.n, self.m, self)
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = ParseRecipeError;
+
+    fn from_str(s: &str) -> Result<Recipe, ParseRecipeError> {
+        let mut slices: Vec<Vec<Vec<&str>>> = vec!(Vec::new());
+        for line in s.lines().map(|line| line.trim()) {
+            if line.is_empty() {
+                if !slices.last().unwrap().is_empty() { slices.push(Vec::new()) }
+            } else {
+                slices.last_mut().unwrap().push(line.split_whitespace().collect());
+            }
+        }
+        if slices.last().unwrap().is_empty() { slices.pop(); }
+        let words: Vec<&str> = slices.iter().flatten().flatten().cloned().collect();
+        let (n, m) = match (slices.first().and_then(|slice| slice.first()), words.first()) {
+            (Some(row), Some(word)) => (row.len(), word.len()),
+            _ => return Err(ParseRecipeError::Shape(String::from("no cells")))
+        };
+        if slices.iter().flatten().any(|row| row.len() != n) {
+            return Err(ParseRecipeError::Shape(format!("rows are not all of {} cells", n)))
+        }
+        if m >= 2 && slices.iter().any(|slice| slice.len() != n) {
+            return Err(ParseRecipeError::Shape(format!("slices are not all of {} rows", n)))
+        }
+        if n.checked_pow(m as u32) != Some(words.len()) {
+            return Err(ParseRecipeError::Shape(format!("{} cells of {} sides", words.len(), m)))
+        }
+        let mut recipe = Recipe::new(n, m);
+        for (coord, word) in recipe.map.coords().clone().iter().zip(words) {
+            if word.len() == m && word.chars().all(|c| c == '.') { continue }
+            let orientation: Orientation = word.bytes().map(|c| c.wrapping_sub(b'a') as usize).collect();
+            let mut sorted = orientation.clone();
+            sorted.sort();
+            sorted.dedup();
+            if orientation.len() != m || sorted.len() != m || orientation.iter().any(|&side| side >= n) {
+                return Err(ParseRecipeError::Word(word.to_string()))
+            }
+            recipe.map.insert(coord, orientation);
+        }
+        Ok(recipe)
+    }
+}
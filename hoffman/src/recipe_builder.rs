@@ -1,30 +1,102 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::cmp::min;
 use std::iter::repeat;
 
 use utils::*;
 use combinatorics::*;
 use plot::*;
+use symbolic::*;
 use super::*;
 
-pub struct RecipeBuilder {
+/// Places bricks by a recipe in one packing per dimension tuple.
+///
+/// The sides are `IntType` unless built `with_sides` of another `Number`.
+pub struct RecipeBuilder<T = IntType> {
     n: usize,
     m: usize,
-    dimension_tuples: Vec<DimensionTuple>,
+    dimension_tuples: Vec<Vec<T>>,
+    /// The side classes of `recipe::side_classes`; sides in a class are interchangeable.
+    classes: Vec<usize>,
     recipe: Recipe,
-    packings: Vec<NdArray<HyperRectangle>>,
+    packings: Vec<NdArray<HyperRectangle<T>>>,
     subgrid_counts: Vec<Vec<HashMap<usize, usize>>>
 }
 
 impl RecipeBuilder {
 
     pub fn new(n: usize, m: usize, dimension_tuples: Vec<DimensionTuple>) -> RecipeBuilder {
+        RecipeBuilder::with_sides(n, m, dimension_tuples.into_iter().map(DimensionTuple::into_vec).collect())
+    }
+
+    pub fn generate(recipe: &Recipe, dimension_tuples: Vec<DimensionTuple>, ) -> RecipeBuilder {
+        RecipeBuilder::generate_with_sides(recipe, dimension_tuples.into_iter().map(DimensionTuple::into_vec).collect())
+    }
+
+    /// The packing of the dimension tuple with the given index as a list of absolute boxes.
+    pub fn box_list(&self, index: usize) -> boxes::BoxList {
+        boxes::BoxList::new(self, &self.dimension_tuples[index], index)
+    }
+
+    /// The bricks that keep their volume when the tuple at `index` has zero sides,
+    /// placed on the grid of the positive sides.
+    ///
+    /// A brick moves back along each axis by the cells before it that are flat
+    /// along that axis, and its orientation is renumbered in the positive sides.
+    /// With one zero side only `n^(m-1) (n-m)` of the `(n-1)^m` cells are
+    /// filled, so the induced recipe is partial, and `generate` only places its
+    /// bricks as here where no predecessor is empty. All pairs of bricks are
+    /// therefore checked with `verify::verify_packing`. Returns `None` if the
+    /// tuple has no zero side, or if the bricks do not fall in distinct cells
+    /// without overlapping.
+    pub fn induced_bricks(&self, index: usize) -> Option<boxes::BoxList> {
+        let dimension_tuple = &self.dimension_tuples[index];
+        let positive: Vec<usize> = (0..self.n).filter(|&i| dimension_tuple[i] > 0).collect();
+        if positive.len() == self.n { return None }
+        let packing = &self.packings[index];
+        let mut cells = HashSet::new();
+        let mut boxes = Vec::new();
+        for (coord, hyper_rectangle) in packing.iter() {
+            if hyper_rectangle.iter().any(|interval| interval.is_degenerate()) { continue }
+            let mut induced = Vec::with_capacity(self.m);
+            for d in 0..self.m {
+                let mut flat = 0;
+                for level in 0..coord[d] {
+                    let mut previous = coord.clone();
+                    previous[d] = level;
+                    if packing.get(&previous)?[d].is_degenerate() { flat += 1 }
+                }
+                if coord[d] - flat >= positive.len() { return None }
+                induced.push(coord[d] - flat);
+            }
+            if !cells.insert(induced.clone()) { return None }
+            let orientation = self.recipe.map.get(coord)?.iter().map(|side| {
+                positive.iter().position(|i| i == side)
+            }).collect::<Option<Orientation>>()?;
+            boxes.push(boxes::PlacedBox { coord: induced, orientation, hyper_rectangle: hyper_rectangle.clone() });
+        }
+        let placed: Vec<(Coord, HyperRectangle)> = boxes.iter().map(|placed| (placed.coord.clone(), placed.hyper_rectangle.clone())).collect();
+        verify::verify_packing(&placed, &number::sum(dimension_tuple)).ok()?;
+        Some(boxes::BoxList { dimension_tuple: positive.iter().map(|&i| dimension_tuple[i]).collect(), boxes })
+    }
+
+    /// Writes the packing for the tuple at `index` as by `NpyArray::from_packing`.
+    pub fn save_packing_npy(&self, index: usize, directory: &String, file_name: &String) {
+        npy::NpyArray::from_packing(self, index).save(directory, file_name);
+    }
+}
+
+impl<T: Number> RecipeBuilder<T> {
+
+    /// A builder for unvalidated tuples of any `Number`, for instance rationals or big integers.
+    pub fn with_sides(n: usize, m: usize, dimension_tuples: Vec<Vec<T>>) -> RecipeBuilder<T> {
         let shape = repeat(n).take(m).collect();
         let dimension_tuple_count = dimension_tuples.len();
+        let classes = if dimension_tuples.is_empty() { (0..n).collect() } else { side_classes(&dimension_tuples) };
         RecipeBuilder {
             n: n,
             m: m,
             dimension_tuples: dimension_tuples,
+            classes,
             recipe: Recipe::new(n, m),
             packings: repeat(NdArray::new(&shape)).take(dimension_tuple_count).collect(),
             subgrid_counts: repeat(
@@ -33,8 +105,8 @@ impl RecipeBuilder {
         }
     }
 
-    pub fn generate(recipe: &Recipe, dimension_tuples: Vec<DimensionTuple>, ) -> RecipeBuilder {
-        let mut recipe_builder = RecipeBuilder::new(recipe.n, recipe.m, dimension_tuples);
+    pub fn generate_with_sides(recipe: &Recipe, dimension_tuples: Vec<Vec<T>>) -> RecipeBuilder<T> {
+        let mut recipe_builder = RecipeBuilder::with_sides(recipe.n, recipe.m, dimension_tuples);
         for coord in recipe.map.coords() {
             match recipe.map.get(coord) {
                 Some(orientation) => recipe_builder.insert(coord, orientation),
@@ -48,6 +120,38 @@ impl RecipeBuilder {
         &self.recipe
     }
 
+    /// The packing of the dimension tuple with the given index.
+    pub fn get_packing(&self, index: usize) -> &NdArray<HyperRectangle<T>> {
+        &self.packings[index]
+    }
+
+    /// Verifies the packing of the dimension tuple with the given index with `verify::verify_packing`,
+    /// which compares every pair of bricks rather than grid neighbours only.
+    pub fn verify_packing(&self, index: usize) -> Result<verify::Certificate<T>, verify::VerificationError<T>> {
+        let packing = &self.packings[index];
+        let boxes: Vec<(Coord, HyperRectangle<T>)> = packing.iter().map(|(coord, hyper_rectangle)| {
+            (coord.clone(), hyper_rectangle.clone())
+        }).collect();
+        verify::verify_packing(&boxes, &number::sum(&self.dimension_tuples[index]))
+    }
+
+    pub fn classes(&self) -> &[usize] {
+        &self.classes
+    }
+
+    /// The orientations to try at a coordinate, one for each distinct brick.
+    /// Without repeated sides these are all the permutations.
+    pub fn orientations(&self) -> Vec<Orientation> {
+        let indices: Vec<usize> = (0..self.n).collect();
+        permutations(&indices, self.m).into_iter().filter(|orientation| {
+            &merge_orientation(orientation, &self.classes) == orientation
+        }).collect()
+    }
+
+    fn multiplicity(&self, side: usize) -> usize {
+        self.classes.iter().filter(|&&class| class == self.classes[side]).count()
+    }
+
     pub fn validate(&self) -> bool {
         self.recipe.map.coords().iter().all(|coord| {
             !self.has_overlaps(coord)
@@ -61,14 +165,14 @@ impl RecipeBuilder {
         for (packing, dimension_tuple) in self.packings.iter_mut().zip(self.dimension_tuples.iter()) {
             let hyper_rectangle = {
                 (0..self.m).map(|dim| {
-                    let begin = if coord[dim] == 0 { 0 } else {
+                    let begin = if coord[dim] == 0 { T::zero() } else {
                         let mut index = coord.clone();
                         index[dim] -= 1;
-                        packing.get(&index).map(|rect| rect[dim].end).unwrap_or(0)
+                        packing.get(&index).map(|rect| rect[dim].end.clone()).unwrap_or_else(T::zero)
                     };
                     Interval {
-                        begin: begin,
-                        end: begin + dimension_tuple[orientation[dim]]
+                        begin: begin.clone(),
+                        end: begin + dimension_tuple[orientation[dim]].clone()
                     }
                 }).collect()
             };
@@ -96,15 +200,18 @@ impl RecipeBuilder {
         let orientation = &self.recipe.map.get(coord).unwrap();
 
         for (dim, &c) in coord.iter().enumerate() {
+            let class = self.classes[orientation[dim]];
             let mut index = coord.clone();
+            let mut count = 0;
             for j in 0..c {
                 index[dim] = j;
 
                 match self.recipe.map.get(&index) {
-                    Some(other) => if other[dim] == orientation[dim] { return false },
+                    Some(other) => if self.classes[other[dim]] == class { count += 1 },
                     None => continue
                 };
             }
+            if count >= self.multiplicity(orientation[dim]) { return false }
         }
         true
     }
@@ -112,20 +219,20 @@ impl RecipeBuilder {
     pub fn satisfies_subgrid_criterion(&self, coord: &Coord) -> bool {
         let limit = self.n.pow(self.n as u32 - 2);
         coord.iter().enumerate().all(|(dim, &v)| {
-            self.subgrid_counts[dim][v].values().max().unwrap() <= &limit
+            self.subgrid_counts[dim][v].iter().all(|(&class, &count)| count <= limit * self.multiplicity(class))
         })
     }
 
     fn decrement_subgrid_count(&mut self, coord: &Coord, orientation: &Orientation) {
         for dim in 0..self.m {
-            let count = self.subgrid_counts[dim][coord[dim]].entry(orientation[dim]).or_insert(0);
+            let count = self.subgrid_counts[dim][coord[dim]].entry(self.classes[orientation[dim]]).or_insert(0);
             *count -= 1;
         }
     }
 
     fn increment_subgrid_count(&mut self, coord: &Coord, orientation: &Orientation) {
         for dim in 0..self.m {
-            let count = self.subgrid_counts[dim][coord[dim]].entry(orientation[dim]).or_insert(0);
+            let count = self.subgrid_counts[dim][coord[dim]].entry(self.classes[orientation[dim]]).or_insert(0);
             *count += 1;
         }
     }
@@ -140,7 +247,10 @@ impl RecipeBuilder {
         let neighbours = product(neighbourhood.as_slice());
 
         for packing in &self.packings {
-            let hyper_rectangle = &packing.get(coord).unwrap();
+            let hyper_rectangle = match packing.get(coord) {
+                Some(hyper_rectangle) => hyper_rectangle,
+                None => return false
+            };
 
             // Check for overlap.
             for neighbour in &neighbours {
@@ -149,8 +259,8 @@ impl RecipeBuilder {
                     Some(other) => other,
                     None => continue
                 };
-                // Check if any of the packings has an overlap.
-                if hyper_rectangle.iter().zip(other_hyper_rectangle.iter()).all(|(a, b)| a.intersects(b) ) {
+                // Check if any of the packings has an overlap. Bricks with a zero side have no volume and overlap nothing.
+                if hyper_rectangle.iter().zip(other_hyper_rectangle.iter()).all(|(a, b)| a.overlaps(b) ) {
                     return true
                 }
             }
@@ -158,9 +268,65 @@ impl RecipeBuilder {
         false
     }
 
+    /// The packing with every endpoint written as a linear form in the sides of the brick.
+    pub fn symbolic_packing(&self) -> NdArray<SymbolicHyperRectangle> {
+        let mut packing: NdArray<SymbolicHyperRectangle> = NdArray::new(&vec!(self.n; self.m));
+        for coord in self.recipe.map.coords() {
+            let orientation = match self.recipe.map.get(coord) {
+                Some(orientation) => orientation,
+                None => continue
+            };
+            let hyper_rectangle = (0..self.m).map(|dim| {
+                let begin = if coord[dim] == 0 { LinearForm::zero(self.n) } else {
+                    let mut index = coord.clone();
+                    index[dim] -= 1;
+                    packing.get(&index).map(|rect| rect[dim].end.clone()).unwrap_or_else(|| LinearForm::zero(self.n))
+                };
+                let end = begin.add(&LinearForm::symbol(self.n, orientation[dim]));
+                SymbolicInterval { begin, end }
+            }).collect();
+            packing.insert(coord, hyper_rectangle);
+        }
+        packing
+    }
+
+    /// The conditions on a non-decreasing tuple under which the recipe is valid.
+    ///
+    /// Each pair of neighbours not separated by the order of the sides alone
+    /// yields one condition; alternatives that the order rules out are dropped.
+    pub fn validity_conditions(&self) -> Vec<Condition> {
+        let packing = self.symbolic_packing();
+        let coords: Vec<Coord> = packing.coords().iter().filter(|coord| packing.contains_key(coord)).cloned().collect();
+        let mut conditions = Vec::new();
+        for (i, coord) in coords.iter().enumerate() {
+            let this = packing.get(coord).unwrap();
+            for other in &coords[i + 1..] {
+                if coord.iter().zip(other.iter()).any(|(&a, &b)| a.abs_diff(b) > 1) { continue }
+                let that = packing.get(other).unwrap();
+                let mut alternatives = Vec::new();
+                let mut separated = false;
+                for (a, b) in this.iter().zip(that.iter()) {
+                    for form in &[b.begin.sub(&a.end), a.begin.sub(&b.end)] {
+                        if form.is_ordered_non_negative() {
+                            separated = true
+                        } else if !form.is_ordered_negative() && !alternatives.contains(form) {
+                            alternatives.push(form.clone())
+                        }
+                    }
+                }
+                if !separated {
+                    conditions.push(Condition { coord: coord.clone(), other: other.clone(), alternatives });
+                }
+            }
+        }
+        conditions
+    }
+
     pub fn is_sharp_corner(&self, coord: &Coord) -> bool {
         for packing in &self.packings {
             let this_hyper_rectangle = &packing.get(coord).unwrap();
+            // A flat brick neither rests on nor supports another.
+            if is_flat(this_hyper_rectangle) { continue }
             let possible_directions: Vec<usize> = (0..self.m).filter(|&i| coord[i] > 0).collect();
             let direction_count = possible_directions.len();
             for dimensionality in 1..=direction_count {
@@ -171,8 +337,8 @@ impl RecipeBuilder {
                         foundation_coord[direction] -= 1;
                     }
                     let foundation_hyper_rectangle = match packing.get(&foundation_coord) {
-                        Some(hyper_rectangle) => hyper_rectangle,
-                        None => continue
+                        Some(hyper_rectangle) if !is_flat(hyper_rectangle) => hyper_rectangle,
+                        _ => continue
                     };
                     let other_dims = list_except(&(0..self.m).collect::<Vec<_>>(), &directions);
 
@@ -186,7 +352,7 @@ impl RecipeBuilder {
                             other_coord
                         }).collect();
 
-                        if other_coords.iter().any(|coord| !packing.contains_key(coord)) { continue }
+                        if other_coords.iter().any(|coord| packing.get(coord).is_none_or(|other| is_flat(other))) { continue }
 
                         let other_hyper_rectangles = other_coords.iter().map(|coord| {
                             packing.get(coord).unwrap()
@@ -206,32 +372,53 @@ impl RecipeBuilder {
         false
     }
 
-    pub fn get_rectangles_at(&self, fixed: Vec<(usize, usize)>) -> Vec<Rectangle> {
+    pub fn get_rectangles_at(&self, fixed: Vec<(usize, usize)>) -> Vec<Rectangle<T>> {
+        assert!(fixed.len() + 2 == self.m, "Can only plot in 2D.");
         let fixed_dims: Vec<usize> = fixed.iter().map(|&(dim, _)| dim).collect();
+        let varying_dims = utils::list_except(&(0..self.m).collect::<Vec<_>>(), &fixed_dims);
+        self.get_slice((varying_dims[0], varying_dims[1]), &fixed)
+    }
+
+    /// The cells of the slice through the given axes, where every other axis
+    /// is at the level given for it in `levels` or else at level 0.
+    pub fn slice_coords(&self, axes: &[usize], levels: &[(usize, usize)]) -> Vec<Coord> {
+        self.recipe.map.coords().iter().filter(|coord| {
+            (0..self.m).filter(|axis| !axes.contains(axis)).all(|axis| {
+                let level = levels.iter().find(|&&(dim, _)| dim == axis).map_or(0, |&(_, level)| level);
+                coord[axis] == level
+            })
+        }).cloned().collect()
+    }
+
+    /// The rectangles of the first packing in the plane of the `axes`, drawn
+    /// across and down, at the levels of the `fixed` axes.
+    pub fn get_slice(&self, axes: (usize, usize), fixed: &[(usize, usize)]) -> Vec<Rectangle<T>> {
         assert!(fixed.len() + 2 == self.m, "Can only plot in 2D.");
+        let (across, down) = axes;
         let mut rects = Vec::new();
         for i in 0..self.n {
             for j in 0..self.n {
-                let mut index = vec!(i, j);
-                for &(dim, level) in &fixed {
-                    index.insert(dim, level);
+                let mut index = vec!(0; self.m);
+                for &(dim, level) in fixed {
+                    index[dim] = level;
                 }
-                let varying_dims = utils::list_except(&(0..self.m).collect::<Vec<_>>(), &fixed_dims);
-                match self.packings[0].get(&index) {
-                    Some(hyper_rectangle) => {
-                        let rectangle = plot::Rectangle {
-                            x: hyper_rectangle[varying_dims[0]].begin,
-                            y: hyper_rectangle[varying_dims[1]].begin,
-                            width: hyper_rectangle[varying_dims[0]].width(),
-                            height: hyper_rectangle[varying_dims[1]].width()
-                        };
-                        rects.push(rectangle);
-                    },
-                    None => continue
+                index[across] = i;
+                index[down] = j;
+                if let Some(hyper_rectangle) = self.packings[0].get(&index) {
+                    rects.push(plot::Rectangle {
+                        x: hyper_rectangle[across].begin.clone(),
+                        y: hyper_rectangle[down].begin.clone(),
+                        width: hyper_rectangle[across].width(),
+                        height: hyper_rectangle[down].width()
+                    });
                 }
-
             }
         }
         rects
     }
 }
+
+/// Whether the hyper-rectangle has a side of zero width.
+fn is_flat<T: Number>(hyper_rectangle: &HyperRectangle<T>) -> bool {
+    hyper_rectangle.iter().any(|interval| interval.is_degenerate())
+}
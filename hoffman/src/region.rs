@@ -0,0 +1,88 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Zero, Signed};
+
+use super::*;
+use symbolic::*;
+use sweep::Violation;
+
+/// A half-space `form >= 0` bounding a validity region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Facet {
+    pub form: LinearForm,
+    /// The bricks that collide beyond the facet, or `None` for the facets
+    /// keeping the tuple positive and non-decreasing.
+    pub collision: Option<(Coord, Coord)>,
+    /// How much every side may change at once before the facet is crossed,
+    /// `form(t) / (|c1| + .. + |cn|)` at the tuple `t`.
+    pub margin: BigRational
+}
+
+impl Facet {
+    fn new(form: LinearForm, collision: Option<(Coord, Coord)>, dimension_tuple: &[IntType]) -> Facet {
+        let norm: i64 = form.coefficients.iter().map(|c| c.abs()).sum();
+        let margin = BigRational::new(BigInt::from(form.evaluate(dimension_tuple)), BigInt::from(norm));
+        Facet { form, collision, margin }
+    }
+
+    pub fn contains(&self, point: &[BigRational]) -> bool {
+        let value: BigRational = self.form.coefficients.iter().zip(point.iter())
+            .fold(Zero::zero(), |acc: BigRational, (&c, a)| acc + BigRational::from(BigInt::from(c)) * a);
+        !value.is_negative()
+    }
+}
+
+/// A polyhedron of dimension tuples around a tuple on which a recipe is valid.
+///
+/// Every pair of neighbouring bricks is kept apart by one of its separating
+/// forms, and the region keeps, for each pair, the form with the largest
+/// margin at the tuple. The recipe may well be valid outside the region, as
+/// another form can take over, but it is valid everywhere inside.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidityRegion {
+    pub dimension_tuple: Vec<IntType>,
+    pub facets: Vec<Facet>
+}
+
+impl ValidityRegion {
+    pub fn new(recipe: &Recipe, dimension_tuple: &[IntType]) -> Result<ValidityRegion, Vec<Violation>> {
+        let n = recipe.n;
+        let mut facets = Vec::new();
+        facets.push(Facet::new(LinearForm::symbol(n, 0), None, dimension_tuple));
+        for i in 1..n {
+            let form = LinearForm::symbol(n, i).sub(&LinearForm::symbol(n, i - 1));
+            facets.push(Facet::new(form, None, dimension_tuple));
+        }
+        let mut violations = Vec::new();
+        for condition in RecipeBuilder::generate(recipe, vec!()).validity_conditions() {
+            let collision = Some((condition.coord.clone(), condition.other.clone()));
+            let best = condition.alternatives.iter()
+                .map(|form| Facet::new(form.clone(), collision.clone(), dimension_tuple))
+                .max_by(|a, b| a.margin.cmp(&b.margin));
+            match best {
+                Some(ref facet) if !facet.margin.is_negative() => facets.push(facet.clone()),
+                _ => violations.push(Violation { coord: condition.coord, other: condition.other })
+            }
+        }
+        if violations.is_empty() {
+            Ok(ValidityRegion { dimension_tuple: dimension_tuple.to_vec(), facets })
+        } else {
+            Err(violations)
+        }
+    }
+
+    pub fn contains(&self, point: &[BigRational]) -> bool {
+        self.facets.iter().all(|facet| facet.contains(point))
+    }
+
+    /// The facet beyond which two bricks collide that is closest to the tuple.
+    /// A recipe whose bricks are apart for all tuples has none.
+    pub fn nearest_breaking(&self) -> Option<&Facet> {
+        self.facets.iter().filter(|facet| facet.collision.is_some()).min_by(|a, b| a.margin.cmp(&b.margin))
+    }
+
+    /// How much every side may change at once while staying in the region.
+    pub fn margin(&self) -> BigRational {
+        self.facets.iter().map(|facet| facet.margin.clone()).min().unwrap()
+    }
+}
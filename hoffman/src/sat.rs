@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::*;
+use combinatorics::*;
+use utils::*;
+
+/// A boolean variable of the SAT encoding.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Variable {
+    /// The brick at the coordinate has the given orientation.
+    Orientation(Coord, Orientation),
+    /// The brick at the coordinate has side `k` along dimension `d`.
+    Side(Coord, usize, usize),
+    /// The bricks before the coordinate along dimension `d` use exactly the given sides.
+    Prefix(Coord, usize, Vec<usize>),
+    /// The two bricks are separated along dimension `d`.
+    Separated(Coord, Coord, usize)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SatError {
+    Unsatisfiable,
+    Parse(String),
+    Ambiguous(Coord),
+    Incomplete(Coord),
+    Invalid
+}
+
+impl fmt::Display for SatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SatError::Unsatisfiable => write!(f, "The solver reported the instance unsatisfiable."),
+            SatError::Parse(line) => write!(f, "Could not parse: {}", line),
+            SatError::Ambiguous(coord) => write!(f, "Several orientations chosen at {:?}.", coord),
+            SatError::Incomplete(coord) => write!(f, "No orientation chosen at {:?}.", coord),
+            SatError::Invalid => write!(f, "The decoded recipe is not valid.")
+        }
+    }
+}
+
+/// Numbering of the variables of an encoding, starting from 1 as in DIMACS.
+#[derive(Clone, Debug)]
+pub struct VariableMap {
+    pub n: usize,
+    pub m: usize,
+    pub dimension_tuples: Vec<DimensionTuple>,
+    variables: Vec<Variable>,
+    indices: HashMap<Variable, usize>
+}
+
+impl VariableMap {
+    pub fn new(n: usize, m: usize, dimension_tuples: Vec<DimensionTuple>) -> VariableMap {
+        VariableMap {
+            n,
+            m,
+            dimension_tuples,
+            variables: Vec::new(),
+            indices: HashMap::new()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    pub fn literal(&mut self, variable: Variable) -> i64 {
+        if let Some(&index) = self.indices.get(&variable) {
+            return index as i64
+        }
+        self.variables.push(variable.clone());
+        self.indices.insert(variable, self.variables.len());
+        self.variables.len() as i64
+    }
+
+    pub fn index(&self, variable: &Variable) -> Option<usize> {
+        self.indices.get(variable).cloned()
+    }
+
+    pub fn variable(&self, index: usize) -> Option<&Variable> {
+        if index == 0 { None } else { self.variables.get(index - 1) }
+    }
+
+    /// Turns the literals of a satisfying assignment into a recipe
+    /// and checks it with a `RecipeBuilder` for the mapped dimension tuples.
+    pub fn decode(&self, literals: &[i64]) -> Result<Recipe, SatError> {
+        let mut recipe = Recipe::new(self.n, self.m);
+        for &literal in literals.iter().filter(|&&l| l > 0) {
+            if let Some(Variable::Orientation(coord, orientation)) = self.variable(literal as usize) {
+                if recipe.map.contains_key(coord) {
+                    return Err(SatError::Ambiguous(coord.clone()))
+                }
+                recipe.map.insert(coord, orientation.clone());
+            }
+        }
+        if let Some(coord) = recipe.map.coords().iter().find(|coord| !recipe.map.contains_key(coord)) {
+            return Err(SatError::Incomplete(coord.clone()))
+        }
+        let recipe_builder = RecipeBuilder::generate(&recipe, self.dimension_tuples.clone());
+        let coords = recipe.map.coords();
+        if recipe_builder.validate() && coords.iter().all(|coord| recipe_builder.satisfies_line_criterion(coord)) {
+            Ok(recipe)
+        } else {
+            Err(SatError::Invalid)
+        }
+    }
+
+    pub fn save(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_string(), &format!("exports/{}", directory), &format!("{}.map", file_name))
+          .expect("Error writing variable map to file");
+    }
+
+    pub fn load(directory: &String, file_name: &String) -> VariableMap {
+        let contents = utils::read_file(directory, &format!("{}.map", file_name))
+          .expect("Error reading variable map from file.");
+        VariableMap::parse(&contents).expect("Error reading variable map from file.")
+    }
+
+    pub fn parse(contents: &str) -> Result<VariableMap, SatError> {
+        let mut map = VariableMap::new(0, 0, Vec::new());
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let error = || SatError::Parse(line.to_string());
+            match words[0] {
+                "n" => map.n = words.get(1).and_then(|v| v.parse().ok()).ok_or_else(error)?,
+                "m" => map.m = words.get(1).and_then(|v| v.parse().ok()).ok_or_else(error)?,
+                "tuple" => {
+                    let sides = words[1..].iter().map(|v| v.parse()).collect::<Result<_, _>>().map_err(|_| error())?;
+                    // Only the sides are saved, so the tuple is read back with the weakest
+                    // checks, which take in strict, repeated-side and zero-side tuples alike.
+                    map.dimension_tuples.push(DimensionTuple::with_zero_sides(sides).map_err(|_| error())?);
+                },
+                _ => {
+                    let index: usize = words[0].parse().map_err(|_| error())?;
+                    let variable = parse_variable(&words[1..]).ok_or_else(error)?;
+                    if index != map.len() + 1 {
+                        return Err(error())
+                    }
+                    map.literal(variable);
+                }
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl fmt::Display for VariableMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "n {}", self.n)?;
+        writeln!(f, "m {}", self.m)?;
+        for dimension_tuple in &self.dimension_tuples {
+            writeln!(f, "tuple {}", join(dimension_tuple, " "))?;
+        }
+        for (i, variable) in self.variables.iter().enumerate() {
+            let description = match variable {
+                Variable::Orientation(coord, orientation) => format!("x {} {}", join(coord, ","), join(orientation, ",")),
+                Variable::Side(coord, d, k) => format!("y {} {} {}", join(coord, ","), d, k),
+                Variable::Prefix(coord, d, sides) => format!("s {} {} {}", join(coord, ","), d, join(sides, ",")),
+                Variable::Separated(a, b, d) => format!("sep {} {} {}", join(a, ","), join(b, ","), d)
+            };
+            writeln!(f, "{} {}", i + 1, description)?;
+        }
+        Ok(())
+    }
+}
+
+fn join<T: ToString>(list: &[T], separator: &str) -> String {
+    list.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(separator)
+}
+
+fn parse_list(word: &str) -> Option<Vec<usize>> {
+    word.split(',').map(|v| v.parse().ok()).collect()
+}
+
+fn parse_variable(words: &[&str]) -> Option<Variable> {
+    match (words.first(), words.len()) {
+        (Some(&"x"), 3) => Some(Variable::Orientation(parse_list(words[1])?, parse_list(words[2])?)),
+        (Some(&"y"), 4) => Some(Variable::Side(parse_list(words[1])?, words[2].parse().ok()?, words[3].parse().ok()?)),
+        (Some(&"s"), 4) => Some(Variable::Prefix(parse_list(words[1])?, words[2].parse().ok()?, parse_list(words[3])?)),
+        (Some(&"sep"), 4) => Some(Variable::Separated(parse_list(words[1])?, parse_list(words[2])?, words[3].parse().ok()?)),
+        _ => None
+    }
+}
+
+/// Reads the literals from a solver's output. Both the competition format
+/// (`s SATISFIABLE` followed by `v ...` lines) and a bare list of literals are accepted.
+pub fn parse_model(output: &str) -> Result<Vec<i64>, SatError> {
+    let mut literals = Vec::new();
+    let has_value_lines = output.lines().any(|line| line.starts_with("v "));
+    for line in output.lines().map(|line| line.trim()) {
+        if line.contains("UNSAT") {
+            return Err(SatError::Unsatisfiable)
+        }
+        let values = if let Some(values) = line.strip_prefix("v ") {
+            values
+        } else if has_value_lines || line.is_empty() || line.starts_with('c') || line.starts_with('s') || line == "SAT" {
+            continue
+        } else {
+            line
+        };
+        for value in values.split_whitespace() {
+            let literal: i64 = value.parse().map_err(|_| SatError::Parse(line.to_string()))?;
+            if literal != 0 {
+                literals.push(literal);
+            }
+        }
+    }
+    Ok(literals)
+}
+
+/// CNF encoding of the packing problem solved by `RecipeBuilder`.
+///
+/// Besides the orientation of each brick, the encoding tracks which sides
+/// precede a brick along each line. This gives every brick's interval along
+/// each dimension as a subset sum, so neighbouring bricks that would overlap
+/// for one of the dimension tuples can be excluded clause by clause.
+pub struct SatEncoding {
+    pub variables: VariableMap,
+    pub clauses: Vec<Vec<i64>>
+}
+
+impl SatEncoding {
+    pub fn new(n: usize, m: usize, dimension_tuples: Vec<DimensionTuple>, symmetry_breaking: bool) -> SatEncoding {
+        let mut encoding = SatEncoding {
+            variables: VariableMap::new(n, m, dimension_tuples),
+            clauses: Vec::new()
+        };
+        let coords = make_coords(&vec!(n; m));
+        let orientations = permutations(&(0..n).collect::<Vec<_>>(), m);
+
+        // Allocate orientation variables first so they get the lowest indices.
+        for coord in &coords {
+            for orientation in &orientations {
+                encoding.variables.literal(Variable::Orientation(coord.clone(), orientation.clone()));
+            }
+        }
+        for coord in &coords {
+            encoding.add_exactly_one(coord, &orientations);
+            encoding.add_sides(coord, &orientations);
+            encoding.add_line_criterion(coord);
+        }
+        for (i, a) in coords.iter().enumerate() {
+            for b in &coords[i + 1..] {
+                if are_diagonal_neighbours(a, b) {
+                    encoding.add_non_overlap(a, b);
+                }
+            }
+        }
+        if symmetry_breaking {
+            encoding.add_symmetry_breaking(&coords[0], &orientations);
+        }
+        encoding
+    }
+
+    fn add_exactly_one(&mut self, coord: &Coord, orientations: &[Orientation]) {
+        let literals: Vec<i64> = orientations.iter().map(|orientation| {
+            self.variables.literal(Variable::Orientation(coord.clone(), orientation.clone()))
+        }).collect();
+        for (i, &a) in literals.iter().enumerate() {
+            for &b in &literals[i + 1..] {
+                self.clauses.push(vec!(-a, -b));
+            }
+        }
+        self.clauses.push(literals);
+    }
+
+    fn add_sides(&mut self, coord: &Coord, orientations: &[Orientation]) {
+        for orientation in orientations {
+            let x = self.variables.literal(Variable::Orientation(coord.clone(), orientation.clone()));
+            for (d, &k) in orientation.iter().enumerate() {
+                let y = self.variables.literal(Variable::Side(coord.clone(), d, k));
+                self.clauses.push(vec!(-x, y));
+            }
+        }
+    }
+
+    fn prefix(&mut self, coord: &Coord, d: usize, sides: Vec<usize>) -> Option<i64> {
+        if sides.is_empty() { None } else { Some(self.variables.literal(Variable::Prefix(coord.clone(), d, sides))) }
+    }
+
+    /// Propagates the prefix of each brick to its successor along each line
+    /// and forbids repeating a side along a line.
+    fn add_line_criterion(&mut self, coord: &Coord) {
+        let n = self.variables.n;
+        for d in 0..self.variables.m {
+            let mut next = coord.clone();
+            next[d] += 1;
+            for sides in combinations(&(0..n).collect::<Vec<_>>(), coord[d]) {
+                let prefix = self.prefix(coord, d, sides.clone());
+                for k in 0..n {
+                    let y = self.variables.literal(Variable::Side(coord.clone(), d, k));
+                    let mut clause: Vec<i64> = prefix.iter().map(|&s| -s).collect();
+                    clause.push(-y);
+                    if !sides.contains(&k) {
+                        if next[d] == n { continue }
+                        let mut next_sides = sides.clone();
+                        next_sides.push(k);
+                        next_sides.sort();
+                        clause.push(self.prefix(&next, d, next_sides).unwrap());
+                    }
+                    self.clauses.push(clause);
+                }
+            }
+        }
+    }
+
+    fn add_non_overlap(&mut self, a: &Coord, b: &Coord) {
+        let n = self.variables.n;
+        let sides: Vec<usize> = (0..n).collect();
+        let separators: Vec<i64> = (0..self.variables.m).map(|d| {
+            self.variables.literal(Variable::Separated(a.clone(), b.clone(), d))
+        }).collect();
+        for (d, &separator) in separators.iter().enumerate() {
+            for prefix_a in combinations(&sides, a[d]) {
+                for prefix_b in combinations(&sides, b[d]) {
+                    for k_a in list_except(&sides, &prefix_a) {
+                        for k_b in list_except(&sides, &prefix_b) {
+                            let overlaps = self.variables.dimension_tuples.iter().any(|tuple| {
+                                interval(tuple, &prefix_a, k_a).intersects(&interval(tuple, &prefix_b, k_b))
+                            });
+                            if !overlaps { continue }
+                            let mut clause = vec!(-separator);
+                            clause.extend(self.prefix(a, d, prefix_a.clone()).map(|s| -s));
+                            clause.push(-self.variables.literal(Variable::Side(a.clone(), d, k_a)));
+                            clause.extend(self.prefix(b, d, prefix_b.clone()).map(|s| -s));
+                            clause.push(-self.variables.literal(Variable::Side(b.clone(), d, k_b)));
+                            self.clauses.push(clause);
+                        }
+                    }
+                }
+            }
+        }
+        self.clauses.push(separators);
+    }
+
+    /// Any packing can be rotated such that the orientation at the origin is increasing.
+    fn add_symmetry_breaking(&mut self, origin: &Coord, orientations: &[Orientation]) {
+        for orientation in orientations {
+            if orientation.windows(2).any(|w| w[0] > w[1]) {
+                let x = self.variables.literal(Variable::Orientation(origin.clone(), orientation.clone()));
+                self.clauses.push(vec!(-x));
+            }
+        }
+    }
+
+    pub fn to_dimacs(&self) -> String {
+        let mut dimacs = String::new();
+        dimacs.push_str(&format!("c Hoffman packing n = {}, m = {}\n", self.variables.n, self.variables.m));
+        for dimension_tuple in &self.variables.dimension_tuples {
+            dimacs.push_str(&format!("c tuple {}\n", join(dimension_tuple, " ")));
+        }
+        dimacs.push_str(&format!("p cnf {} {}\n", self.variables.len(), self.clauses.len()));
+        for clause in &self.clauses {
+            dimacs.push_str(&format!("{} 0\n", join(clause, " ")));
+        }
+        dimacs
+    }
+
+    /// Writes the `.cnf` file together with its `.map` file.
+    pub fn save_dimacs(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_dimacs(), &format!("exports/{}", directory), &format!("{}.cnf", file_name))
+          .expect("Error writing CNF to file");
+        self.variables.save(directory, file_name);
+    }
+
+    pub fn decode_model(&self, output: &str) -> Result<Recipe, SatError> {
+        self.variables.decode(&parse_model(output)?)
+    }
+}
+
+fn interval(dimension_tuple: &DimensionTuple, prefix: &[usize], side: usize) -> Interval {
+    let begin: IntType = prefix.iter().map(|&i| dimension_tuple[i]).sum();
+    Interval {
+        begin,
+        end: begin + dimension_tuple[side]
+    }
+}
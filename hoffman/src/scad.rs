@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use super::*;
+
+/// Sizes of a printed puzzle, in millimetres.
+#[derive(Clone, Debug)]
+pub struct PuzzleOptions {
+    /// Length of a unit of the sides.
+    pub scale: f64,
+    /// Play between neighbouring bricks and between the bricks and the walls
+    /// of the box. Every brick is this much shorter than its place along
+    /// every axis.
+    pub clearance: f64,
+    /// Thickness of the walls and floor of the box and of the lid.
+    pub wall: f64,
+    /// Space between the parts on the print bed.
+    pub spacing: f64
+}
+
+impl Default for PuzzleOptions {
+    fn default() -> PuzzleOptions {
+        PuzzleOptions { scale: 5.0, clearance: 0.2, wall: 2.0, spacing: 5.0 }
+    }
+}
+
+/// A brick at its place in the packing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Brick {
+    pub coord: Coord,
+    /// The orientation with equal sides merged, which names the part.
+    pub orientation: Orientation,
+    pub hyper_rectangle: HyperRectangle
+}
+
+impl Brick {
+    fn sizes(&self) -> Vec<IntType> {
+        self.hyper_rectangle.iter().map(|interval| interval.width()).collect()
+    }
+
+    /// Whether the projections onto the floor share an area.
+    fn covers(&self, other: &Brick) -> bool {
+        self.hyper_rectangle[..2].iter().zip(&other.hyper_rectangle[..2]).all(|(a, b)| a.overlaps(b))
+    }
+
+    fn bottom(&self) -> IntType {
+        self.hyper_rectangle[2].begin
+    }
+
+    fn top(&self) -> IntType {
+        self.hyper_rectangle[2].end
+    }
+}
+
+/// A three-dimensional packing as a puzzle to print: the bricks, a box that
+/// holds the packed cube and a lid for it. The last axis points up.
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    pub dimension_tuple: DimensionTuple,
+    pub bricks: Vec<Brick>,
+    pub options: PuzzleOptions
+}
+
+impl Puzzle {
+    pub fn new(recipe: &Recipe, dimension_tuple: &DimensionTuple, options: PuzzleOptions) -> Puzzle {
+        assert_eq!(recipe.m, 3, "Can only print three-dimensional packings.");
+        let recipe_builder = RecipeBuilder::generate(recipe, vec!(dimension_tuple.clone()));
+        let packing = recipe_builder.get_packing(0);
+        let bricks = recipe.map.coords().iter().filter_map(|coord| {
+            recipe.map.get(coord).map(|orientation| Brick {
+                coord: coord.clone(),
+                orientation: recipe::merge_orientation(orientation, recipe_builder.classes()),
+                hyper_rectangle: packing.get(coord).unwrap().clone()
+            })
+        }).collect();
+        Puzzle { dimension_tuple: dimension_tuple.clone(), bricks, options }
+    }
+
+    /// The side of the packed cube in units.
+    pub fn side(&self) -> IntType {
+        self.dimension_tuple.iter().sum()
+    }
+
+    /// The bricks grouped by orientation, in order of orientation.
+    pub fn parts_list(&self) -> Vec<(Orientation, Vec<Coord>)> {
+        let mut parts: BTreeMap<Orientation, Vec<Coord>> = BTreeMap::new();
+        for brick in &self.bricks {
+            parts.entry(brick.orientation.clone()).or_default().push(brick.coord.clone());
+        }
+        parts.into_iter().collect()
+    }
+
+    /// An order in which to lower the bricks into the box so that every brick
+    /// comes to rest on the floor or on top of bricks placed before it, and
+    /// no brick placed before it is in the way from above. Of the bricks that
+    /// can go next the lowest is taken, and among those the first in
+    /// coordinate order.
+    ///
+    /// Fails with the bricks that could not be placed when the rest of them
+    /// hang over gaps in the packing or are covered by the bricks above them.
+    pub fn assembly_order(&self) -> Result<Vec<Coord>, Vec<Coord>> {
+        let mut placed = vec!(false; self.bricks.len());
+        let mut order = Vec::new();
+        while order.len() < self.bricks.len() {
+            let is_placed = |i: &usize| placed[*i];
+            let next = (0..self.bricks.len()).filter(|i| !is_placed(i)).filter(|&i| {
+                let brick = &self.bricks[i];
+                let below = |j: &usize| is_placed(j) && self.bricks[*j].top() == brick.bottom() && self.bricks[*j].covers(brick);
+                let above = |j: &usize| is_placed(j) && self.bricks[*j].bottom() >= brick.top() && self.bricks[*j].covers(brick);
+                let supported = brick.bottom() == 0 || (0..self.bricks.len()).any(|j| below(&j));
+                supported && !(0..self.bricks.len()).any(|j| above(&j))
+            }).min_by_key(|&i| (self.bricks[i].bottom(), i));
+            match next {
+                Some(i) => {
+                    placed[i] = true;
+                    order.push(self.bricks[i].coord.clone());
+                },
+                None => {
+                    return Err((0..self.bricks.len()).filter(|&i| !placed[i]).map(|i| self.bricks[i].coord.clone()).collect())
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// An OpenSCAD script whose `part` variable picks what to render: the
+    /// bricks, the box and the lid laid out for printing, or the puzzle as
+    /// assembled. The sizes are variables at the top of the script, so the
+    /// scale and the tolerances can be tuned without exporting again. The
+    /// parts list and the assembly order head the script as comments.
+    pub fn to_scad(&self) -> String {
+        let coord_str = |coord: &Coord| format!("({})", coord.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "));
+        let sizes_str = |sizes: &[IntType]| sizes.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+        let n = self.dimension_tuple.len();
+        let colors = plot::rgb_palette((0..3).map(|i| n - i).product());
+        let color_str = |brick: &Brick| {
+            let (r, g, b) = colors[binary::orientation_rank(&brick.orientation, n) as usize];
+            format!("[{:.3}, {:.3}, {:.3}]", r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0)
+        };
+
+        let mut scad = format!("// Hoffman puzzle of {} bricks of sides {} packed in a cube of side {}.\n//\n",
+            self.bricks.len(), sizes_str(&self.dimension_tuple), self.side());
+        scad.push_str("// Parts, by orientation:\n");
+        for (orientation, coords) in self.parts_list() {
+            let sizes: Vec<IntType> = orientation.iter().map(|&side| self.dimension_tuple[side]).collect();
+            scad.push_str(&format!("//   {} ({}) x {}: {}\n", recipe::orientation_word(&orientation), sizes_str(&sizes),
+                coords.len(), coords.iter().map(coord_str).collect::<Vec<_>>().join(" ")));
+        }
+        scad.push_str("//\n");
+        match self.assembly_order() {
+            Ok(order) => {
+                scad.push_str("// Assembly order, lowering every brick into the box:\n");
+                for (step, coord) in order.iter().enumerate() {
+                    let brick = self.bricks.iter().find(|brick| &brick.coord == coord).unwrap();
+                    scad.push_str(&format!("//   {:>2}. {} {}\n", step + 1, coord_str(coord), recipe::orientation_word(&brick.orientation)));
+                }
+            },
+            Err(remaining) => {
+                scad.push_str(&format!("// No assembly order: {} cannot be lowered onto support.\n",
+                    remaining.iter().map(coord_str).collect::<Vec<_>>().join(" ")));
+            }
+        }
+
+        let options = &self.options;
+        scad.push_str("\n// \"print\" or \"assembled\"\npart = \"print\";\n\n");
+        scad.push_str(&format!("scale = {};\nclearance = {};\nwall = {};\nspacing = {};\n\n", options.scale, options.clearance, options.wall, options.spacing));
+        scad.push_str(&format!("inner = {} * scale + clearance;\nouter = inner + 2 * wall;\n", self.side()));
+        let longest = self.bricks.iter().flat_map(|brick| brick.sizes()).max().unwrap_or(0);
+        scad.push_str(&format!("pitch = {} * scale + spacing;\n\n", longest));
+        scad.push_str("module brick(sizes) {\n    cube([for (size = sizes) size * scale - clearance]);\n}\n\n");
+        scad.push_str("// Open at the top, with room above the bricks for the rim of the lid.\n");
+        scad.push_str("module box() {\n    difference() {\n        cube([outer, outer, inner + 2 * wall]);\n");
+        scad.push_str("        translate([wall, wall, wall]) cube([inner, inner, inner + 2 * wall]);\n    }\n}\n\n");
+        scad.push_str("// A plate with a rim that fits into the opening of the box.\nmodule lid() {\n    cube([outer, outer, wall]);\n");
+        scad.push_str("    translate([wall + clearance / 2, wall + clearance / 2, wall]) difference() {\n");
+        scad.push_str("        cube([inner - clearance, inner - clearance, wall]);\n");
+        scad.push_str("        translate([wall, wall, -1]) cube([inner - clearance - 2 * wall, inner - clearance - 2 * wall, wall + 2]);\n    }\n}\n\n");
+
+        // On the print bed every brick lies on its largest face.
+        let columns = (self.bricks.len() as f64).sqrt().ceil() as usize;
+        scad.push_str("module print() {\n");
+        for (i, brick) in self.bricks.iter().enumerate() {
+            let mut sizes = brick.sizes();
+            sizes.sort_by(|a, b| b.cmp(a));
+            scad.push_str(&format!("    color({}) translate([{} * pitch, {} * pitch, 0]) brick([{}]); // {} {}\n",
+                color_str(brick), i % columns, i / columns, sizes_str(&sizes), coord_str(&brick.coord), recipe::orientation_word(&brick.orientation)));
+        }
+        scad.push_str(&format!("    translate([{} * pitch, 0, 0]) box();\n", columns));
+        scad.push_str(&format!("    translate([{} * pitch + outer + spacing, 0, 0]) lid();\n}}\n\n", columns));
+
+        scad.push_str("module assembled() {\n    %box();\n");
+        for brick in &self.bricks {
+            let begins: Vec<IntType> = brick.hyper_rectangle.iter().map(|interval| interval.begin).collect();
+            scad.push_str(&format!("    color({}) translate([for (begin = [{}]) wall + begin * scale + clearance]) brick([{}]); // {} {}\n",
+                color_str(brick), sizes_str(&begins), sizes_str(&brick.sizes()), coord_str(&brick.coord), recipe::orientation_word(&brick.orientation)));
+        }
+        scad.push_str("}\n\nif (part == \"print\") print();\nif (part == \"assembled\") assembled();\n");
+        scad
+    }
+
+    pub fn save_scad(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_scad(), &format!("exports/{}", directory), &format!("{}.scad", file_name))
+          .expect("Error writing OpenSCAD script to file");
+    }
+}
@@ -0,0 +1,296 @@
+use std::fmt;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use serde_json::Value;
+use num_bigint::BigInt;
+use num_traits::{Zero, One, Signed};
+
+use super::*;
+use combinatorics::*;
+use dimension_tuple::*;
+use simplex::*;
+
+/// A comparison of the sums of two disjoint sets of side indices.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Comparison {
+    pub lhs: Vec<usize>,
+    pub rhs: Vec<usize>
+}
+
+impl Comparison {
+    pub fn compare(&self, dimension_tuple: &[IntType]) -> Ordering {
+        let sum = |indices: &[usize]| indices.iter().map(|&i| dimension_tuple[i]).sum::<IntType>();
+        sum(&self.lhs).cmp(&sum(&self.rhs))
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms = |indices: &[usize]| indices.iter().map(|i| format!("a{}", i + 1)).collect::<Vec<_>>().join(" + ");
+        write!(f, "{} vs {}", terms(&self.lhs), terms(&self.rhs))
+    }
+}
+
+/// The comparisons of subset sums that are not decided for every tuple satisfying Hoffman's inequality.
+///
+/// Common sides cancel, so only disjoint sets are compared. Under Hoffman's
+/// inequality a set of more sides always has the larger sum, and of two sets
+/// of the same size the one dominated elementwise has the smaller sum, so
+/// what remains are the equal-sized sets where neither dominates the other.
+pub fn undecided_comparisons(n: usize) -> Vec<Comparison> {
+    let indices: Vec<usize> = (0..n).collect();
+    let mut comparisons = Vec::new();
+    for k in 2..=n / 2 {
+        for lhs in combinations(&indices, k) {
+            let rest = utils::list_except(&indices, &lhs);
+            for rhs in combinations(&rest, k) {
+                if rhs < lhs { continue }
+                let dominated = |a: &[usize], b: &[usize]| a.iter().zip(b.iter()).all(|(x, y)| x < y);
+                if !dominated(&lhs, &rhs) && !dominated(&rhs, &lhs) {
+                    comparisons.push(Comparison { lhs: lhs.clone(), rhs });
+                }
+            }
+        }
+    }
+    comparisons
+}
+
+/// The outcomes of the undecided comparisons, which fix the order of all subset sums.
+pub type Signature = Vec<Ordering>;
+
+pub fn signature_str(signature: &[Ordering]) -> String {
+    signature.iter().map(|ordering| match ordering {
+        Ordering::Less => '<',
+        Ordering::Equal => '=',
+        Ordering::Greater => '>'
+    }).collect()
+}
+
+fn parse_signature(signature: &str) -> Signature {
+    signature.chars().map(|c| match c {
+        '<' => Ordering::Less,
+        '>' => Ordering::Greater,
+        _ => Ordering::Equal
+    }).collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureEntry {
+    pub signature: Signature,
+    /// The tuple with the smallest sum, and lexicographically first among those, having the signature.
+    pub representative: DimensionTuple,
+    /// Number of tuples up to the sum bound having the signature.
+    pub count: usize
+}
+
+/// The signatures realised by tuples with unique sums satisfying Hoffman's inequality.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureTable {
+    pub n: usize,
+    pub max_sum: IntType,
+    pub comparisons: Vec<Comparison>,
+    pub entries: Vec<SignatureEntry>
+}
+
+impl SignatureTable {
+    /// Visits the tuples by increasing sum up to `max_sum`, so a signature
+    /// is only missing from the table if all its tuples have larger sums.
+    pub fn enumerate(n: usize, max_sum: IntType) -> SignatureTable {
+        let comparisons = undecided_comparisons(n);
+        let mut entries: Vec<SignatureEntry> = Vec::new();
+        let mut positions: HashMap<Signature, usize> = HashMap::new();
+        for sum in (n * (n + 1) / 2) as IntType..=max_sum {
+            hoffman_tuples_with_sum(n, sum, &mut |sides| {
+                let signature: Signature = comparisons.iter().map(|comparison| comparison.compare(sides)).collect();
+                if signature.contains(&Ordering::Equal) || !utils::list_has_unique_sums(sides) { return }
+                match positions.get(&signature) {
+                    Some(&position) => entries[position].count += 1,
+                    None => {
+                        positions.insert(signature.clone(), entries.len());
+                        entries.push(SignatureEntry {
+                            signature,
+                            representative: DimensionTuple::new(sides.to_vec()).unwrap(),
+                            count: 1
+                        });
+                    }
+                }
+            });
+        }
+        SignatureTable { n, max_sum, comparisons, entries }
+    }
+
+    pub fn signature(&self, dimension_tuple: &[IntType]) -> Signature {
+        self.comparisons.iter().map(|comparison| comparison.compare(dimension_tuple)).collect()
+    }
+
+    pub fn representatives(&self) -> Vec<DimensionTuple> {
+        self.entries.iter().map(|entry| entry.representative.clone()).collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let list = |indices: &[usize]| format!("[{}]", indices.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "));
+        let comparisons: Vec<String> = self.comparisons.iter().map(|comparison| {
+            format!("{{ \"lhs\": {}, \"rhs\": {} }}", list(&comparison.lhs), list(&comparison.rhs))
+        }).collect();
+        let entries: Vec<String> = self.entries.iter().map(|entry| {
+            format!("{{ \"signature\": \"{}\", \"representative\": {:?}, \"count\": {} }}",
+                signature_str(&entry.signature), entry.representative, entry.count)
+        }).collect();
+        format!("{{ \"n\": {}, \"max_sum\": {}, \"comparisons\": [{}],\n\"signatures\": [\n{}\n] }}",
+            self.n, self.max_sum, comparisons.join(", "), entries.join(",\n"))
+    }
+
+    pub fn save_json(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_json(), &format!("exports/{}", directory), &format!("{}.json", file_name))
+          .expect("Error writing signature table to file");
+    }
+
+    pub fn load_json(directory: &String, file_name: &String) -> SignatureTable {
+        let json = utils::read_file(directory, &format!("{}.json", file_name))
+          .expect("Error reading signature table from file.");
+        SignatureTable::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> SignatureTable {
+        let table: Value = serde_json::from_str(json)
+          .expect("Error reading signature table from file.");
+        let numbers = |value: &Value| -> Vec<u64> {
+            value.as_array().unwrap().iter().map(|v| v.as_u64().unwrap()).collect()
+        };
+        let indices = |value: &Value| -> Vec<usize> { numbers(value).iter().map(|&v| v as usize).collect() };
+        SignatureTable {
+            n: table["n"].as_u64().unwrap() as usize,
+            max_sum: table["max_sum"].as_u64().unwrap() as IntType,
+            comparisons: table["comparisons"].as_array().unwrap().iter().map(|comparison| {
+                Comparison { lhs: indices(&comparison["lhs"]), rhs: indices(&comparison["rhs"]) }
+            }).collect(),
+            entries: table["signatures"].as_array().unwrap().iter().map(|entry| {
+                let sides = numbers(&entry["representative"]).iter().map(|&v| v as IntType).collect();
+                SignatureEntry {
+                    signature: parse_signature(entry["signature"].as_str().unwrap()),
+                    representative: DimensionTuple::new(sides).expect("Invalid representative in signature table."),
+                    count: entry["count"].as_u64().unwrap() as usize
+                }
+            }).collect()
+        }
+    }
+
+    /// The table of every realisable signature.
+    ///
+    /// Signatures without a tuple up to `max_sum` get the witness found by
+    /// `realise` as representative, with a count of zero. A witness has unique
+    /// sums, since every comparison of subset sums is either decided under
+    /// Hoffman's inequality or among those it makes strict.
+    pub fn complete(n: usize, max_sum: IntType) -> SignatureTable {
+        let mut table = SignatureTable::enumerate(n, max_sum);
+        let known: Vec<Signature> = table.entries.iter().map(|entry| entry.signature.clone()).collect();
+        for (signature, witness) in realisable_patterns(n, true) {
+            if known.contains(&signature) { continue }
+            table.entries.push(SignatureEntry {
+                signature,
+                representative: DimensionTuple::new(witness).expect("Witness of a signature does not have unique sums."),
+                count: 0
+            });
+        }
+        table
+    }
+
+    /// `SignatureTable::complete`, read from `directory` when it has been computed before.
+    pub fn cached(n: usize, max_sum: IntType, directory: &String) -> SignatureTable {
+        let file_name = format!("{}d-complete.json", n);
+        if let Ok(json) = utils::read_file(directory, &file_name) {
+            let table = SignatureTable::from_json(&json);
+            if table.n == n && table.max_sum == max_sum {
+                return table
+            }
+        }
+        let table = SignatureTable::complete(n, max_sum);
+        utils::write_file(&table.to_json(), directory, &file_name)
+          .expect("Error writing signature table to file");
+        table
+    }
+}
+
+/// A positive increasing integer tuple, optionally satisfying Hoffman's
+/// inequality, on which the first comparisons have the outcomes of `pattern`.
+///
+/// The constraints are homogeneous, so the strict inequalities may be asked
+/// to hold with a margin of one, which turns the question into the
+/// feasibility of a linear program that is decided exactly. The witness need
+/// not have unique sums outside the compared sets.
+pub fn realise(n: usize, comparisons: &[Comparison], pattern: &[Ordering], hoffman: bool) -> Option<Vec<IntType>> {
+    let mut program = LinearProgram::new(n);
+    let mut coefficients = vec!(0; n);
+    coefficients[0] = 1;
+    program.add_constraint(&coefficients, Relation::GreaterEqual, 1);
+    for i in 1..n {
+        let mut coefficients = vec!(0; n);
+        coefficients[i] = 1;
+        coefficients[i - 1] = -1;
+        program.add_constraint(&coefficients, Relation::GreaterEqual, 1);
+    }
+    if hoffman {
+        let mut coefficients = vec!(-1; n);
+        coefficients[0] = n as i64;
+        program.add_constraint(&coefficients, Relation::GreaterEqual, 1);
+    }
+    for (comparison, &ordering) in comparisons.iter().zip(pattern.iter()) {
+        let mut coefficients = vec!(0; n);
+        for &i in &comparison.lhs { coefficients[i] += 1 }
+        for &i in &comparison.rhs { coefficients[i] -= 1 }
+        match ordering {
+            Ordering::Greater => program.add_constraint(&coefficients, Relation::GreaterEqual, 1),
+            Ordering::Less => program.add_constraint(&coefficients, Relation::LessEqual, -1),
+            Ordering::Equal => program.add_constraint(&coefficients, Relation::Equal, 0)
+        }
+    }
+    let point = program.feasible_point()?;
+    let scale = point.iter().fold(BigInt::one(), |acc, v| {
+        let divisor = gcd(&acc, v.denom());
+        acc / divisor * v.denom()
+    });
+    let integers: Vec<BigInt> = point.iter().map(|v| v.numer() * (&scale / v.denom())).collect();
+    let divisor = integers.iter().fold(Zero::zero(), |acc: BigInt, v| gcd(&acc, v));
+    Some(integers.iter().map(|v| {
+        IntType::try_from(v / &divisor).expect("Witness has a side too large for IntType.")
+    }).collect())
+}
+
+fn gcd(a: &BigInt, b: &BigInt) -> BigInt {
+    if b.is_zero() { a.abs() } else { gcd(b, &(a % b)) }
+}
+
+/// Every realisable pattern of strict outcomes of the undecided comparisons, each with a witness.
+///
+/// Patterns are extended one comparison at a time and abandoned as soon as a
+/// prefix is not realisable; this is meant for `n <= 6`.
+pub fn realisable_patterns(n: usize, hoffman: bool) -> Vec<(Signature, Vec<IntType>)> {
+    let comparisons = undecided_comparisons(n);
+    let mut patterns = Vec::new();
+    let mut stack: Vec<(Signature, Vec<IntType>)> = Vec::new();
+    if let Some(witness) = realise(n, &comparisons, &[], hoffman) {
+        stack.push((Vec::new(), witness));
+    }
+    while let Some((pattern, witness)) = stack.pop() {
+        if pattern.len() == comparisons.len() {
+            patterns.push((pattern, witness));
+            continue
+        }
+        for &ordering in &[Ordering::Greater, Ordering::Less] {
+            let mut extended = pattern.clone();
+            extended.push(ordering);
+            // The witness of the prefix often already decides the next comparison.
+            let witness = if comparisons[pattern.len()].compare(&witness) == ordering {
+                Some(witness.clone())
+            } else {
+                realise(n, &comparisons, &extended, hoffman)
+            };
+            if let Some(witness) = witness {
+                stack.push((extended, witness));
+            }
+        }
+    }
+    patterns.sort();
+    patterns
+}
@@ -0,0 +1,141 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Zero, One, Signed};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Relation {
+    LessEqual,
+    GreaterEqual,
+    Equal
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearConstraint {
+    pub coefficients: Vec<BigRational>,
+    pub relation: Relation,
+    pub rhs: BigRational
+}
+
+/// A system of linear constraints on non-negative variables, solved exactly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearProgram {
+    pub variable_count: usize,
+    pub constraints: Vec<LinearConstraint>
+}
+
+impl LinearProgram {
+    pub fn new(variable_count: usize) -> LinearProgram {
+        LinearProgram { variable_count, constraints: Vec::new() }
+    }
+
+    pub fn add_constraint(&mut self, coefficients: &[i64], relation: Relation, rhs: i64) {
+        assert!(coefficients.len() == self.variable_count, "Constraint has the wrong number of coefficients.");
+        self.constraints.push(LinearConstraint {
+            coefficients: coefficients.iter().map(|&c| BigRational::from(BigInt::from(c))).collect(),
+            relation,
+            rhs: BigRational::from(BigInt::from(rhs))
+        });
+    }
+
+    /// A point satisfying every constraint, found by the first phase of the
+    /// simplex method. Bland's rule rules out cycling, so this always terminates.
+    pub fn feasible_point(&self) -> Option<Vec<BigRational>> {
+        let n = self.variable_count;
+        let rows = self.constraints.len();
+        let extra_count = self.constraints.iter().filter(|c| c.relation != Relation::Equal).count();
+        let artificial_begin = n + extra_count;
+        let artificial_count = self.constraints.iter().filter(|c| {
+            c.relation == Relation::Equal || (c.relation == Relation::LessEqual) == c.rhs.is_negative()
+        }).count();
+        let columns = artificial_begin + artificial_count;
+
+        // Every row gets `rhs >= 0` and a starting basic variable: a slack where possible, otherwise an artificial.
+        let mut tableau: Vec<Vec<BigRational>> = Vec::with_capacity(rows);
+        let mut basis: Vec<usize> = Vec::with_capacity(rows);
+        let (mut extra, mut artificial) = (n, artificial_begin);
+        for constraint in &self.constraints {
+            let mut row = vec!(BigRational::zero(); columns + 1);
+            let sign = if constraint.rhs.is_negative() { -BigRational::one() } else { BigRational::one() };
+            for (j, c) in constraint.coefficients.iter().enumerate() {
+                row[j] = &sign * c;
+            }
+            row[columns] = &sign * &constraint.rhs;
+            let slack = match constraint.relation {
+                Relation::Equal => None,
+                Relation::LessEqual => Some(sign.clone()),
+                Relation::GreaterEqual => Some(-sign.clone())
+            };
+            match slack {
+                Some(s) => {
+                    let is_positive = s.is_positive();
+                    row[extra] = s;
+                    extra += 1;
+                    if is_positive {
+                        basis.push(extra - 1);
+                    } else {
+                        row[artificial] = BigRational::one();
+                        basis.push(artificial);
+                        artificial += 1;
+                    }
+                },
+                None => {
+                    row[artificial] = BigRational::one();
+                    basis.push(artificial);
+                    artificial += 1;
+                }
+            }
+            tableau.push(row);
+        }
+
+        // The sum of the artificials is `w - d·x`, to be brought down to zero.
+        let mut objective = vec!(BigRational::zero(); columns + 1);
+        for (row, &basic) in tableau.iter().zip(basis.iter()) {
+            if basic < artificial_begin { continue }
+            for (o, v) in objective.iter_mut().zip(row.iter()) {
+                *o += v;
+            }
+        }
+        for o in objective[artificial_begin..columns].iter_mut() {
+            *o = BigRational::zero();
+        }
+
+        while let Some(entering) = (0..artificial_begin).find(|&j| objective[j].is_positive()) {
+            let leaving = (0..rows).filter(|&i| tableau[i][entering].is_positive()).min_by(|&i, &k| {
+                let (ri, rk) = (&tableau[i][columns] / &tableau[i][entering], &tableau[k][columns] / &tableau[k][entering]);
+                ri.cmp(&rk).then(basis[i].cmp(&basis[k]))
+            });
+            let leaving = match leaving {
+                Some(leaving) => leaving,
+                None => break
+            };
+            let pivot = tableau[leaving][entering].clone();
+            for v in tableau[leaving].iter_mut() {
+                *v /= &pivot;
+            }
+            let pivot_row = tableau[leaving].clone();
+            for (i, row) in tableau.iter_mut().enumerate() {
+                let factor = row[entering].clone();
+                if i == leaving || factor.is_zero() { continue }
+                for (v, p) in row.iter_mut().zip(pivot_row.iter()) {
+                    *v -= &factor * p;
+                }
+            }
+            let factor = objective[entering].clone();
+            for (v, p) in objective.iter_mut().zip(pivot_row.iter()) {
+                *v -= &factor * p;
+            }
+            basis[leaving] = entering;
+        }
+
+        if !objective[columns].is_zero() {
+            return None
+        }
+        let mut point = vec!(BigRational::zero(); n);
+        for (row, &basic) in tableau.iter().zip(basis.iter()) {
+            if basic < n {
+                point[basic] = row[columns].clone();
+            }
+        }
+        Some(point)
+    }
+}
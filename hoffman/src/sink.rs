@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use super::*;
+use archive::RecipeArchive;
+
+/// Receives the solutions of a search one at a time.
+///
+/// `push` returns whether the sink let the solution through, so that in a
+/// pair `(a, b)` the sink `b` only sees what `a` lets through. A pair is a
+/// sink itself, and so is a mutable reference to one, which lets a search
+/// feed a chain like `(&mut count, (Dedupe::new(), archive_sink))` while the
+/// caller keeps hold of the count.
+pub trait SolutionSink {
+    fn push(&mut self, recipe: &Recipe) -> bool;
+}
+
+impl<S: SolutionSink> SolutionSink for &mut S {
+    fn push(&mut self, recipe: &Recipe) -> bool {
+        (**self).push(recipe)
+    }
+}
+
+impl<A: SolutionSink, B: SolutionSink> SolutionSink for (A, B) {
+    fn push(&mut self, recipe: &Recipe) -> bool {
+        self.0.push(recipe) && self.1.push(recipe)
+    }
+}
+
+/// Keeps every solution.
+impl SolutionSink for Vec<Recipe> {
+    fn push(&mut self, recipe: &Recipe) -> bool {
+        Vec::push(self, recipe.clone());
+        true
+    }
+}
+
+/// Counts the solutions without keeping them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Count {
+    pub count: usize
+}
+
+impl Count {
+    pub fn new() -> Count {
+        Count { count: 0 }
+    }
+}
+
+impl SolutionSink for Count {
+    fn push(&mut self, _recipe: &Recipe) -> bool {
+        self.count += 1;
+        true
+    }
+}
+
+/// Lets through the first solution of every symmetry class.
+///
+/// The canonical form of each class is held under its `Recipe::canonical_hash`,
+/// and classes whose hashes collide are told apart by their canonical forms,
+/// as in `RecipeArchive::lookup`.
+#[derive(Clone, Debug, Default)]
+pub struct Dedupe {
+    seen: HashMap<String, Vec<Recipe>>
+}
+
+impl Dedupe {
+    pub fn new() -> Dedupe {
+        Dedupe { seen: HashMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.values().map(|canonicals| canonicals.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl SolutionSink for Dedupe {
+    fn push(&mut self, recipe: &Recipe) -> bool {
+        let canonical = recipe.canonical();
+        let canonicals = self.seen.entry(recipe.canonical_hash()).or_default();
+        if canonicals.contains(&canonical) {
+            return false
+        }
+        canonicals.push(canonical);
+        true
+    }
+}
+
+/// A uniform sample of at most `capacity` solutions, kept by reservoir sampling.
+///
+/// The sample depends only on the seed and the order of the solutions, so a
+/// search can be repeated with the same result.
+pub struct Sample {
+    pub capacity: usize,
+    pub recipes: Vec<Recipe>,
+    seen: usize,
+    state: u64
+}
+
+impl Sample {
+    pub fn new(capacity: usize, seed: u64) -> Sample {
+        Sample { capacity, recipes: Vec::with_capacity(capacity), seen: 0, state: seed | 1 }
+    }
+
+    /// Xorshift, which is plenty for picking positions in a sample.
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl SolutionSink for Sample {
+    fn push(&mut self, recipe: &Recipe) -> bool {
+        self.seen += 1;
+        if self.recipes.len() < self.capacity {
+            self.recipes.push(recipe.clone());
+        } else {
+            let position = (self.next() % self.seen as u64) as usize;
+            if position < self.capacity {
+                self.recipes[position] = recipe.clone();
+            }
+        }
+        true
+    }
+}
+
+/// Appends the solutions to an archive as `{prefix}-1`, `{prefix}-2`, ..
+pub struct ArchiveSink {
+    pub archive: RecipeArchive,
+    pub prefix: String
+}
+
+impl ArchiveSink {
+    pub fn new(archive: RecipeArchive, prefix: &str) -> ArchiveSink {
+        ArchiveSink { archive, prefix: prefix.to_string() }
+    }
+}
+
+impl SolutionSink for ArchiveSink {
+    fn push(&mut self, recipe: &Recipe) -> bool {
+        let name = format!("{}-{}", self.prefix, self.archive.len() + 1);
+        self.archive.append(&name, recipe).expect("Error writing recipe to archive.");
+        true
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+use std::thread;
+use std::convert::TryFrom;
+use serde_json::Value;
+
+use super::*;
+use symbolic::*;
+use utils::*;
+
+/// The dimension tuples visited by a sweep.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TupleSource {
+    /// Every non-decreasing tuple of `n` sides from `1..limit`.
+    Range { n: usize, limit: IntType },
+    /// `count` sorted tuples of `n` sides drawn uniformly from `1..limit`, reproducible by `seed`.
+    Random { n: usize, limit: IntType, count: usize, seed: u64 }
+}
+
+impl TupleSource {
+    pub fn tuples(&self) -> Box<dyn Iterator<Item = Vec<IntType>>> {
+        match *self {
+            TupleSource::Range { n, limit } => Box::new(RangeTuples { limit, next: if limit > 1 { Some(vec!(1; n)) } else { None } }),
+            TupleSource::Random { n, limit, count, seed } => {
+                assert!(limit >= 2, "Cannot draw sides from 1..{}.", limit);
+                let mut state = seed.max(1);
+                Box::new((0..count).map(move |_| {
+                    let mut tuple: Vec<IntType> = (0..n).map(|_| 1 + (xorshift(&mut state) % (limit as u64 - 1)) as IntType).collect();
+                    tuple.sort();
+                    tuple
+                }))
+            }
+        }
+    }
+}
+
+struct RangeTuples {
+    limit: IntType,
+    next: Option<Vec<IntType>>
+}
+
+impl Iterator for RangeTuples {
+    type Item = Vec<IntType>;
+
+    fn next(&mut self) -> Option<Vec<IntType>> {
+        let current = self.next.take()?;
+        if let Some(i) = current.iter().rposition(|&a| a + 1 < self.limit) {
+            let mut next = current.clone();
+            let value = next[i] + 1;
+            for a in next[i..].iter_mut() {
+                *a = value;
+            }
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Two bricks of a recipe that overlap for a dimension tuple.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub coord: Coord,
+    pub other: Coord
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} overlaps {:?}", self.coord, self.other)
+    }
+}
+
+/// A recipe, given by its index in the swept list, that is invalid for a dimension tuple.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Failure {
+    pub recipe: usize,
+    pub dimension_tuple: Vec<IntType>,
+    pub violations: Vec<Violation>
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let violations: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        write!(f, "Recipe {} fails for {:?}: {}", self.recipe, self.dimension_tuple, violations.join(", "))
+    }
+}
+
+/// Why a sweep did not resume from its checkpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SweepError {
+    /// The checkpoint could not be parsed.
+    Corrupt(String),
+    /// The checkpoint was saved by a sweep of another source, given as saved.
+    OtherSource(String),
+    /// The checkpoint was saved by a sweep of another list of recipes.
+    OtherRecipes
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SweepError::Corrupt(reason) => write!(f, "Could not read sweep checkpoint: {}", reason),
+            SweepError::OtherSource(source) => write!(f, "Checkpoint belongs to a sweep of {}.", source),
+            SweepError::OtherRecipes => write!(f, "Checkpoint belongs to a sweep of other recipes.")
+        }
+    }
+}
+
+/// FNV-1a hash of the `Recipe::canonical_hash` of every recipe in order, as
+/// the failures of a report refer to the recipes by their index.
+fn recipes_hash(recipes: &[Recipe]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for recipe in recipes {
+        for byte in recipe.canonical_hash().bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SweepReport {
+    /// Number of tuples taken from the source, including those filtered out.
+    pub position: usize,
+    /// Number of tuples that passed the filters and were checked.
+    pub checked: usize,
+    pub failures: Vec<Failure>
+}
+
+impl SweepReport {
+    fn to_json(&self, source: &TupleSource, recipes_hash: &str) -> String {
+        let failures: Vec<String> = self.failures.iter().map(|failure| {
+            let violations: Vec<String> = failure.violations.iter().map(|v| {
+                format!("{{ \"coord\": {:?}, \"other\": {:?} }}", v.coord, v.other)
+            }).collect();
+            format!("{{ \"recipe\": {}, \"dimension_tuple\": {:?}, \"violations\": [{}] }}",
+                failure.recipe, failure.dimension_tuple, violations.join(", "))
+        }).collect();
+        format!("{{ \"source\": {:?}, \"recipes\": \"{}\", \"position\": {}, \"checked\": {}, \"failures\": [\n{}\n] }}",
+            format!("{:?}", source), recipes_hash, self.position, self.checked, failures.join(",\n"))
+    }
+
+    fn from_json(json: &str, source: &TupleSource, recipes_hash: &str) -> Result<SweepReport, SweepError> {
+        let report: Value = serde_json::from_str(json).map_err(|e| SweepError::Corrupt(e.to_string()))?;
+        if report["source"].as_str() != Some(&format!("{:?}", source)) {
+            return Err(SweepError::OtherSource(report["source"].to_string()))
+        }
+        if report["recipes"].as_str() != Some(recipes_hash) {
+            return Err(SweepError::OtherRecipes)
+        }
+        let number = |value: &Value| -> Option<usize> { usize::try_from(value.as_u64()?).ok() };
+        let numbers = |value: &Value| -> Option<Vec<usize>> {
+            value.as_array()?.iter().map(number).collect()
+        };
+        let failures = report["failures"].as_array().and_then(|failures| failures.iter().map(|failure| {
+            Some(Failure {
+                recipe: number(&failure["recipe"])?,
+                dimension_tuple: numbers(&failure["dimension_tuple"])?.into_iter().map(|v| IntType::try_from(v).ok()).collect::<Option<_>>()?,
+                violations: failure["violations"].as_array()?.iter().map(|v| {
+                    Some(Violation { coord: numbers(&v["coord"])?, other: numbers(&v["other"])? })
+                }).collect::<Option<_>>()?
+            })
+        }).collect::<Option<Vec<_>>>());
+        match (number(&report["position"]), number(&report["checked"]), failures) {
+            (Some(position), Some(checked), Some(failures)) => Ok(SweepReport { position, checked, failures }),
+            _ => Err(SweepError::Corrupt(String::from("missing or malformed fields")))
+        }
+    }
+}
+
+/// Checks a list of recipes against many dimension tuples at once.
+///
+/// Each recipe is reduced to its symbolic validity conditions once, so a
+/// tuple is checked by evaluating linear forms instead of building packings.
+/// The tuples are processed in batches spread over `threads`, and with a
+/// checkpoint the report is saved after every batch and picked up again by
+/// the next run over the same source and recipes.
+pub struct Sweep {
+    pub source: TupleSource,
+    pub threads: usize,
+    pub batch_size: usize,
+    pub hoffman_only: bool,
+    pub unique_sums_only: bool,
+    /// Directory and file name of the checkpoint.
+    pub checkpoint: Option<(String, String)>
+}
+
+impl Sweep {
+    pub fn new(source: TupleSource) -> Sweep {
+        Sweep {
+            source,
+            threads: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            batch_size: 1 << 14,
+            hoffman_only: true,
+            unique_sums_only: true,
+            checkpoint: None
+        }
+    }
+
+    pub fn accepts(&self, dimension_tuple: &[IntType]) -> bool {
+        (!self.hoffman_only || dimension_tuple::satisfies_hoffman(dimension_tuple))
+        && (!self.unique_sums_only || list_has_unique_sums(dimension_tuple))
+    }
+
+    /// Runs the sweep, calling `progress` after every batch.
+    ///
+    /// Fails without checking anything when the checkpoint cannot be read or
+    /// was saved by a sweep of another source or another list of recipes.
+    pub fn run<F: FnMut(&SweepReport)>(&self, recipes: &[Recipe], mut progress: F) -> Result<SweepReport, SweepError> {
+        let conditions: Vec<Vec<Condition>> = recipes.iter().map(|recipe| {
+            RecipeBuilder::generate(recipe, vec!()).validity_conditions()
+        }).collect();
+        let recipes_hash = if self.checkpoint.is_some() { recipes_hash(recipes) } else { String::new() };
+        let mut report = match self.checkpoint {
+            Some((ref directory, ref file_name)) => match utils::read_file(directory, file_name) {
+                Ok(json) => SweepReport::from_json(&json, &self.source, &recipes_hash)?,
+                Err(_) => SweepReport::default()
+            },
+            None => SweepReport::default()
+        };
+        let mut tuples = self.source.tuples().skip(report.position);
+        loop {
+            let batch: Vec<Vec<IntType>> = tuples.by_ref().take(self.batch_size).collect();
+            if batch.is_empty() { break }
+            let chunk_size = batch.len().div_ceil(self.threads.max(1));
+            let results: Vec<(usize, Vec<Failure>)> = thread::scope(|scope| {
+                let handles: Vec<_> = batch.chunks(chunk_size).map(|chunk| {
+                    let conditions = &conditions;
+                    scope.spawn(move || self.check(conditions, chunk))
+                }).collect();
+                handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+            });
+            report.position += batch.len();
+            for (checked, failures) in results {
+                report.checked += checked;
+                report.failures.extend(failures);
+            }
+            if let Some((ref directory, ref file_name)) = self.checkpoint {
+                utils::write_file(&report.to_json(&self.source, &recipes_hash), directory, file_name)
+                  .expect("Error writing sweep checkpoint to file");
+            }
+            progress(&report);
+        }
+        Ok(report)
+    }
+
+    fn check(&self, conditions: &[Vec<Condition>], tuples: &[Vec<IntType>]) -> (usize, Vec<Failure>) {
+        let mut checked = 0;
+        let mut failures = Vec::new();
+        for dimension_tuple in tuples {
+            if !self.accepts(dimension_tuple) { continue }
+            checked += 1;
+            for (recipe, recipe_conditions) in conditions.iter().enumerate() {
+                let violations: Vec<Violation> = recipe_conditions.iter()
+                    .filter(|condition| !condition.holds(dimension_tuple))
+                    .map(|condition| Violation { coord: condition.coord.clone(), other: condition.other.clone() })
+                    .collect();
+                if !violations.is_empty() {
+                    failures.push(Failure { recipe, dimension_tuple: dimension_tuple.clone(), violations });
+                }
+            }
+        }
+        (checked, failures)
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use super::*;
+
+/// A linear form `c1 a1 + .. + cn an` in the entries of a dimension tuple.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinearForm {
+    pub coefficients: Vec<i64>
+}
+
+impl LinearForm {
+    pub fn zero(n: usize) -> LinearForm {
+        LinearForm { coefficients: vec!(0; n) }
+    }
+
+    /// The symbol `a(i + 1)`.
+    pub fn symbol(n: usize, i: usize) -> LinearForm {
+        let mut form = LinearForm::zero(n);
+        form.coefficients[i] = 1;
+        form
+    }
+
+    pub fn add(&self, other: &LinearForm) -> LinearForm {
+        LinearForm {
+            coefficients: self.coefficients.iter().zip(other.coefficients.iter()).map(|(a, b)| a + b).collect()
+        }
+    }
+
+    pub fn sub(&self, other: &LinearForm) -> LinearForm {
+        LinearForm {
+            coefficients: self.coefficients.iter().zip(other.coefficients.iter()).map(|(a, b)| a - b).collect()
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.iter().all(|&c| c == 0)
+    }
+
+    pub fn evaluate(&self, dimension_tuple: &[IntType]) -> i64 {
+        self.coefficients.iter().zip(dimension_tuple.iter()).map(|(&c, &a)| c * a as i64).sum()
+    }
+
+    /// Whether the form is non-negative on every positive non-decreasing tuple.
+    ///
+    /// Such a tuple is a non-negative combination of the tuples `(0, .., 0, 1, .., 1)`,
+    /// so this is decided by the suffix sums of the coefficients.
+    pub fn is_ordered_non_negative(&self) -> bool {
+        self.suffix_sums().iter().all(|&s| s >= 0)
+    }
+
+    /// Whether the form is negative on every positive non-decreasing tuple.
+    pub fn is_ordered_negative(&self) -> bool {
+        let suffix_sums = self.suffix_sums();
+        suffix_sums[0] < 0 && suffix_sums.iter().all(|&s| s <= 0)
+    }
+
+    fn suffix_sums(&self) -> Vec<i64> {
+        (0..self.coefficients.len()).map(|k| self.coefficients[k..].iter().sum()).collect()
+    }
+}
+
+fn write_terms(f: &mut fmt::Formatter, terms: &[(usize, i64)]) -> fmt::Result {
+    if terms.is_empty() {
+        return write!(f, "0")
+    }
+    let terms: Vec<String> = terms.iter().map(|&(i, c)| {
+        if c == 1 { format!("a{}", i + 1) } else { format!("{}a{}", c, i + 1) }
+    }).collect();
+    write!(f, "{}", terms.join(" + "))
+}
+
+/// Shown as the inequality `form >= 0`, with the negative terms moved to the left.
+impl fmt::Display for LinearForm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lhs: Vec<(usize, i64)> = self.coefficients.iter().enumerate().filter(|&(_, &c)| c < 0).map(|(i, &c)| (i, -c)).collect();
+        let rhs: Vec<(usize, i64)> = self.coefficients.iter().enumerate().filter(|&(_, &c)| c > 0).map(|(i, &c)| (i, c)).collect();
+        write_terms(f, &lhs)?;
+        write!(f, " <= ")?;
+        write_terms(f, &rhs)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SymbolicInterval {
+    pub begin: LinearForm,
+    pub end: LinearForm
+}
+
+pub type SymbolicHyperRectangle = Vec<SymbolicInterval>;
+
+/// The bricks at `coord` and `other` are disjoint exactly when one of the
+/// alternatives, each an inequality `form >= 0`, holds.
+///
+/// Every endpoint of a packing satisfying the line criterion is a subset
+/// sum, so for tuples with unique sums no alternative vanishes, and the
+/// alternatives may as well be read as strict inequalities.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    pub coord: Coord,
+    pub other: Coord,
+    pub alternatives: Vec<LinearForm>
+}
+
+impl Condition {
+    pub fn holds(&self, dimension_tuple: &[IntType]) -> bool {
+        self.alternatives.iter().any(|form| form.evaluate(dimension_tuple) >= 0)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let alternatives: Vec<String> = self.alternatives.iter().map(|form| form.to_string()).collect();
+        write!(f, "{:?} and {:?}: {}", self.coord, self.other, alternatives.join(" or "))
+    }
+}
+
+/// The extreme rays of the closed cone of tuples satisfying Hoffman's inequality,
+/// `0 < a1 < .. < an` and `a1 + .. + an < (n + 1) a1`.
+///
+/// Apart from `(1, .., 1)`, the `j`th ray is `(w, .., w, w + 1, .., w + 1)`
+/// with `w = n - j + 1` and the first `w + 1` at position `j`. The cone is
+/// simplicial, so the tuples are exactly the positive combinations of the rays.
+pub fn hoffman_rays(n: usize) -> Vec<Vec<i64>> {
+    let mut rays = vec!(vec!(1; n));
+    for j in 1..n {
+        let w = (n - j) as i64;
+        rays.push((0..n).map(|i| if i < j { w } else { w + 1 }).collect());
+    }
+    rays
+}
+
+/// Whether some tuple satisfying Hoffman's inequality makes every alternative fail.
+///
+/// Writing the tuple as a positive combination `λ` of the Hoffman rays, this
+/// asks for `λ > 0` with every form negative. The region is open, so it also
+/// contains tuples with unique sums, and after scaling it is the system
+/// `λ >= 1`, `form(λ) <= -1`, which is decided exactly by Fourier-Motzkin elimination.
+pub fn has_hoffman_counterexample(alternatives: &[LinearForm]) -> bool {
+    let n = match alternatives.first() {
+        Some(form) => form.coefficients.len(),
+        None => return true
+    };
+    let rays = hoffman_rays(n);
+    // Rows (c, b) meaning c·λ <= b.
+    let mut rows: Vec<(Vec<i128>, i128)> = Vec::new();
+    for j in 0..n {
+        let mut c = vec!(0; n);
+        c[j] = -1;
+        rows.push((c, -1));
+    }
+    for form in alternatives {
+        let c = rays.iter().map(|ray| {
+            form.coefficients.iter().zip(ray.iter()).map(|(&a, &r)| (a * r) as i128).sum()
+        }).collect();
+        rows.push((c, -1));
+    }
+    for variable in 0..n {
+        let (mut eliminated, mut positive, mut negative) = (Vec::new(), Vec::new(), Vec::new());
+        for row in rows {
+            match row.0[variable] {
+                0 => eliminated.push(row),
+                c if c > 0 => positive.push(row),
+                _ => negative.push(row)
+            }
+        }
+        for (p, pb) in &positive {
+            for (q, qb) in &negative {
+                let (s, t) = (-q[variable], p[variable]);
+                let c: Vec<i128> = p.iter().zip(q.iter()).map(|(a, b)| s * a + t * b).collect();
+                eliminated.push(normalise(c, s * pb + t * qb));
+            }
+        }
+        eliminated.sort();
+        eliminated.dedup();
+        rows = eliminated;
+    }
+    rows.iter().all(|&(_, b)| b >= 0)
+}
+
+fn normalise(c: Vec<i128>, b: i128) -> (Vec<i128>, i128) {
+    let divisor = c.iter().fold(b.abs(), |acc, &v| gcd(acc, v.abs()));
+    if divisor <= 1 {
+        return (c, b)
+    }
+    (c.iter().map(|v| v / divisor).collect(), b / divisor)
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
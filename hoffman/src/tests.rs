@@ -1,6 +1,11 @@
 use utils::*;
 use combinatorics::*;
 use interval::*;
+use sat::*;
+use sweep::*;
+use signatures::*;
+use super::*;
+use std::cmp::Ordering;
 
 #[test]
 fn interval_is_zero() {
@@ -142,3 +147,1104 @@ fn list_except_test() {
     assert_eq!(list_except(&a, &c), [1, 3]);
     assert_eq!(list_except(&c, &a), [6]);
 }
+
+fn tuple(sides: Vec<IntType>) -> DimensionTuple {
+    DimensionTuple::new(sides).unwrap()
+}
+
+/// A small DPLL solver with unit propagation, enough for the 2D and 3D encodings.
+fn dpll(clauses: &[Vec<i64>], variable_count: usize) -> Option<Vec<i64>> {
+    fn propagate(clauses: &[Vec<i64>], assignment: &mut [i8]) -> bool {
+        loop {
+            let mut changed = false;
+            for clause in clauses {
+                let mut unassigned = None;
+                let mut free = 0;
+                let mut satisfied = false;
+                for &literal in clause {
+                    let value = assignment[literal.unsigned_abs() as usize] * literal.signum() as i8;
+                    if value > 0 { satisfied = true; break }
+                    if value == 0 { free += 1; unassigned = Some(literal); }
+                }
+                if satisfied { continue }
+                match (free, unassigned) {
+                    (0, _) => return false,
+                    (1, Some(literal)) => {
+                        assignment[literal.unsigned_abs() as usize] = literal.signum() as i8;
+                        changed = true;
+                    },
+                    _ => continue
+                }
+            }
+            if !changed { return true }
+        }
+    }
+
+    fn search(clauses: &[Vec<i64>], assignment: &mut Vec<i8>) -> bool {
+        if !propagate(clauses, assignment) { return false }
+        let variable = match (1..assignment.len()).find(|&v| assignment[v] == 0) {
+            Some(variable) => variable,
+            None => return true
+        };
+        for &value in &[1, -1] {
+            let mut next = assignment.clone();
+            next[variable] = value;
+            if search(clauses, &mut next) {
+                *assignment = next;
+                return true
+            }
+        }
+        false
+    }
+
+    let mut assignment = vec!(0; variable_count + 1);
+    if search(clauses, &mut assignment) {
+        Some((1..=variable_count).map(|v| if assignment[v] > 0 { v as i64 } else { -(v as i64) }).collect())
+    } else {
+        None
+    }
+}
+
+fn parse_dimacs(dimacs: &str) -> (usize, Vec<Vec<i64>>) {
+    let mut variable_count = 0;
+    let mut clauses = Vec::new();
+    for line in dimacs.lines() {
+        if line.starts_with('c') { continue }
+        if line.starts_with("p cnf") {
+            variable_count = line.split_whitespace().nth(2).unwrap().parse().unwrap();
+            continue
+        }
+        let literals: Vec<i64> = line.split_whitespace().map(|v| v.parse().unwrap()).collect();
+        clauses.push(literals[..literals.len() - 1].to_vec());
+    }
+    (variable_count, clauses)
+}
+
+fn solve_with_dpll(encoding: &SatEncoding) -> String {
+    let (variable_count, clauses) = parse_dimacs(&encoding.to_dimacs());
+    assert_eq!(variable_count, encoding.variables.len());
+    assert_eq!(clauses.len(), encoding.clauses.len());
+    let model = dpll(&clauses, variable_count).expect("Encoding should be satisfiable.");
+    let values = model.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ");
+    format!("s SATISFIABLE\nv {} 0\n", values)
+}
+
+#[test]
+fn sat_encoding_squares() {
+    let dimension_tuples = vec!(tuple(vec!(8, 9, 10, 12)), tuple(vec!(10, 12, 13, 14)));
+    let encoding = SatEncoding::new(4, 2, dimension_tuples.clone(), true);
+    let output = solve_with_dpll(&encoding);
+    let recipe = encoding.decode_model(&output).unwrap();
+    assert!(RecipeBuilder::generate(&recipe, dimension_tuples).validate());
+    assert!(recipe.map.get(&vec!(0, 0)).unwrap()[0] < recipe.map.get(&vec!(0, 0)).unwrap()[1]);
+
+    let variables = VariableMap::parse(&encoding.variables.to_string()).unwrap();
+    assert_eq!(variables.len(), encoding.variables.len());
+    assert!(variables.decode(&parse_model(&output).unwrap()) == Ok(recipe));
+
+    let dimension_tuples = vec!(DimensionTuple::degenerate(vec!(8, 8, 9, 10)).unwrap(), DimensionTuple::with_zero_sides(vec!(0, 12, 13, 14)).unwrap());
+    let saved = VariableMap::new(4, 2, dimension_tuples.clone()).to_string();
+    assert!(VariableMap::parse(&saved).unwrap().dimension_tuples == dimension_tuples);
+    assert!(VariableMap::parse(&saved.replace("tuple 8 8 9 10", "tuple 9 8 8 10")).is_err());
+}
+
+#[test]
+fn sat_encoding_cubes() {
+    let dimension_tuples = vec!(tuple(vec!(4, 5, 6)));
+    let encoding = SatEncoding::new(3, 3, dimension_tuples.clone(), false);
+    let recipe = encoding.decode_model(&solve_with_dpll(&encoding)).unwrap();
+    let recipe_builder = RecipeBuilder::generate(&recipe, dimension_tuples);
+    assert!(recipe.map.coords().iter().all(|coord| recipe_builder.is_valid(coord)));
+}
+
+#[test]
+fn sat_decode_errors() {
+    let encoding = SatEncoding::new(3, 3, vec!(tuple(vec!(4, 5, 6))), true);
+    assert!(encoding.decode_model("s UNSATISFIABLE\n") == Err(SatError::Unsatisfiable));
+    assert!(encoding.decode_model("v 1 -2 0\n") == Err(SatError::Incomplete(vec!(0, 0, 1))));
+    assert!(encoding.decode_model("v 1 2 0\n") == Err(SatError::Ambiguous(vec!(0, 0, 0))));
+    assert!(encoding.decode_model("v 1 x 0\n") == Err(SatError::Parse(String::from("v 1 x 0"))));
+}
+
+#[test]
+fn gams_model_matches_hand_written_3d() {
+    let model = gams::gams_model(&tuple(vec!(4, 5, 6)));
+    let hand_written = read_file(&String::from("../gams"), &String::from("3D.gms")).unwrap();
+    let overlap_lines = |gms: &str| gms.lines().filter(|line| line.starts_with("inter_") || line.starts_with("or_")).map(String::from).collect::<Vec<_>>();
+    assert_eq!(overlap_lines(&model), overlap_lines(&hand_written));
+    assert!(model.contains("  p   intersects  / 1 * 240 /\n  ovs overlaps    / 1 * 104 /;"));
+    assert!(model.contains("  M(k)  overlap bound\n    / 1  2\n      2  2 /;"));
+    assert_eq!(gams::overlap_bound(&tuple(vec!(57, 59, 62, 63)), 2), 9);
+}
+
+#[test]
+fn gams_listing_import() {
+    let recipe = gams::load_listing(3, &String::from("../gams"), &String::from("3d")).unwrap();
+    assert!(RecipeBuilder::generate(&recipe, vec!(tuple(vec!(4, 5, 6)))).validate());
+    assert_eq!(recipe.map.get(&vec!(0, 0, 0)), Some(&vec!(1, 0, 2)));
+
+    let listing = "---- VAR x  demo\n\n1.1.1.1  .  1.0000  1.0000  EPS\n1.1.1.2  .  1.0000  1.0000  EPS\n";
+    assert!(gams::parse_listing(2, listing) == Err(gams::SolutionError::Ambiguous(vec!(0, 0))));
+    assert!(gams::parse_listing(2, "---- VAR x  demo\n") == Err(gams::SolutionError::Incomplete(vec!(0, 0))));
+    assert!(matches!(gams::load_listing(3, &String::from("../gams"), &String::from("missing")), Err(gams::SolutionError::Read(_))));
+}
+
+#[test]
+fn milp_round_trip_fixed_solution() {
+    let dimension_tuple = tuple(vec!(4, 5, 6));
+    let recipe = gams::load_listing(3, &String::from("../gams"), &String::from("3d")).unwrap();
+    let model = milp::MilpModel::new(&dimension_tuple);
+    assert_eq!(model.constraints.iter().filter(|c| c.name.starts_with("inter_")).count(), 240);
+    assert_eq!(model.constraints.iter().filter(|c| c.name.starts_with("or_")).count(), 104);
+
+    let values = model.assignment(&recipe);
+    assert!(model.violated_constraints(&values).is_empty());
+    assert_eq!(values[model.objective], 0);
+    assert!(milp::parse_solution(3, &model.to_solution(&values)) == Ok(recipe.clone()));
+
+    let cbc = format!("Optimal - objective value 0\n      0 {}  1  0\n", "x_1_1_1_1_2");
+    assert!(milp::parse_solution(3, &cbc) == Err(gams::SolutionError::Incomplete(vec!(0, 0, 0))));
+    assert!(matches!(milp::load_solution(3, &String::from("../gams"), &String::from("missing")), Err(gams::SolutionError::Read(_))));
+
+    let mut valid_count = 0;
+    for permutation in permutations(&[0, 1, 2], 3) {
+        let permuted = recipe.pre_permute(&permutation);
+        let values = model.assignment(&permuted);
+        assert!(model.violated_constraints(&values).is_empty());
+        let valid = RecipeBuilder::generate(&permuted, vec!(dimension_tuple.clone())).validate();
+        assert_eq!(values[model.objective] == 0, valid);
+        if valid { valid_count += 1 }
+    }
+    assert!(valid_count < 6);
+}
+
+#[test]
+fn milp_formats() {
+    let model = milp::MilpModel::new(&tuple(vec!(4, 5, 6)));
+    let lp = model.to_lp();
+    assert!(lp.contains(" inter_1: w_1_1_1_2 - w_1_1_2_2 + 2 u_1 <= 2\n"));
+    assert!(lp.contains(" or_1: u_1 + u_2 + z_1 >= 1\n"));
+    assert!(lp.contains(" q free\n"));
+    assert_eq!(lp.lines().filter(|line| line.contains(':')).count(), model.constraints.len() + 1);
+
+    let mps = model.to_mps();
+    assert!(mps.contains("\n L inter_1\n"));
+    assert!(mps.contains("\n    u_1 inter_1 2\n"));
+    assert!(mps.contains("\n    RHS or_1 1\n"));
+    assert!(mps.contains("\n BV BND x_1_1_1_1_1\n"));
+    assert!(mps.ends_with("ENDATA\n"));
+}
+
+#[test]
+fn symbolic_conditions_3d_packings_are_universal() {
+    let dimension_tuples = vec!(tuple(vec!(4, 5, 6)), tuple(vec!(10, 11, 13)), tuple(vec!(20, 23, 24)));
+    for i in 0..21 {
+        let recipe = packing_3d(i);
+        assert!(recipe.is_universal());
+        let conditions = RecipeBuilder::generate(&recipe, vec!()).validity_conditions();
+        for dimension_tuple in &dimension_tuples {
+            assert!(conditions.iter().all(|condition| condition.holds(dimension_tuple)));
+        }
+    }
+}
+
+#[test]
+fn symbolic_conditions_match_numeric_validation() {
+    let (wide, narrow) = (tuple(vec!(8, 9, 10, 12)), tuple(vec!(10, 12, 13, 14)));
+    let encoding = SatEncoding::new(4, 2, vec!(wide.clone()), false);
+    let recipe = encoding.decode_model(&solve_with_dpll(&encoding)).unwrap();
+    for permutation in permutations(&[0, 1, 2, 3], 4) {
+        let permuted = recipe.pre_permute(&permutation);
+        let conditions = RecipeBuilder::generate(&permuted, vec!()).validity_conditions();
+        let valid_wide = RecipeBuilder::generate(&permuted, vec!(wide.clone())).validate();
+        let valid_narrow = RecipeBuilder::generate(&permuted, vec!(narrow.clone())).validate();
+        assert_eq!(conditions.iter().all(|condition| condition.holds(&wide)), valid_wide);
+        assert_eq!(conditions.iter().all(|condition| condition.holds(&narrow)), valid_narrow);
+        assert_eq!(permuted.is_universal(), valid_wide && valid_narrow);
+    }
+
+    let form = symbolic::LinearForm { coefficients: vec!(1, -1, -1, 1) };
+    assert_eq!(form.to_string(), "a2 + a3 <= a1 + a4");
+    assert!(symbolic::has_hoffman_counterexample(std::slice::from_ref(&form)));
+    assert!(!symbolic::has_hoffman_counterexample(&[form.clone(), form.sub(&form).sub(&form)]));
+}
+
+#[test]
+fn sweep_tuple_sources() {
+    let range: Vec<Vec<IntType>> = TupleSource::Range { n: 3, limit: 5 }.tuples().collect();
+    assert_eq!(range.len(), 20);
+    assert_eq!(range.first(), Some(&vec!(1, 1, 1)));
+    assert_eq!(range.last(), Some(&vec!(4, 4, 4)));
+    assert!(range.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let source = TupleSource::Random { n: 4, limit: 30, count: 50, seed: 7 };
+    let sample: Vec<Vec<IntType>> = source.tuples().collect();
+    assert_eq!(sample.len(), 50);
+    assert!(sample.iter().all(|tuple| tuple.windows(2).all(|pair| pair[0] <= pair[1]) && tuple[0] >= 1 && tuple[3] < 30));
+    assert_eq!(sample, source.tuples().collect::<Vec<_>>());
+}
+
+#[test]
+#[should_panic(expected = "Cannot draw sides from 1..1.")]
+fn sweep_random_tuples_need_a_side() {
+    let _ = TupleSource::Random { n: 3, limit: 1, count: 1, seed: 7 }.tuples();
+}
+
+fn packing_3d(index: usize) -> Recipe {
+    let archive = archive::RecipeArchive::open(&String::from("../universal_counterexamples_3d/res"), &String::from("3d")).unwrap();
+    archive.get(index).unwrap().unwrap().recipe
+}
+
+fn permuted_3d_packings(count: usize) -> Vec<Recipe> {
+    (0..count).flat_map(|i| {
+        let recipe = packing_3d(i);
+        permutations(&[0, 1, 2], 3).iter().map(|p| recipe.pre_permute(p)).collect::<Vec<_>>()
+    }).collect()
+}
+
+#[test]
+fn sweep_matches_numeric_validation() {
+    let recipes = permuted_3d_packings(2);
+    let mut sweep = Sweep::new(TupleSource::Range { n: 3, limit: 16 });
+    let report = sweep.run(&recipes, |_| ()).unwrap();
+    for (i, recipe) in recipes.iter().enumerate() {
+        assert_eq!(report.failures.iter().all(|failure| failure.recipe != i), recipe.is_universal());
+    }
+
+    sweep.hoffman_only = false;
+    sweep.unique_sums_only = false;
+    let report = sweep.run(&recipes, |_| ()).unwrap();
+    assert_eq!(report.checked, report.position);
+    let mut expected = Vec::new();
+    for dimension_tuple in sweep.source.tuples() {
+        for (i, recipe) in recipes.iter().enumerate() {
+            if !RecipeBuilder::generate(recipe, vec!(DimensionTuple::unchecked(dimension_tuple.clone()))).validate() {
+                expected.push((i, dimension_tuple.clone()));
+            }
+        }
+    }
+    let found: Vec<(usize, Vec<IntType>)> = report.failures.iter().map(|f| (f.recipe, f.dimension_tuple.clone())).collect();
+    assert!(!found.is_empty());
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn sweep_resumes_from_checkpoint() {
+    let recipes = permuted_3d_packings(21);
+    let mut sweep = Sweep::new(TupleSource::Range { n: 3, limit: 40 });
+    let universal: Vec<Recipe> = recipes.iter().step_by(6).cloned().collect();
+    assert!(sweep.run(&universal, |_| ()).unwrap().failures.is_empty());
+
+    sweep.source = TupleSource::Random { n: 3, limit: 60, count: 400, seed: 3 };
+    sweep.batch_size = 64;
+    sweep.threads = 3;
+    let expected = sweep.run(&recipes, |_| ()).unwrap();
+    assert!(!expected.failures.is_empty());
+
+    let directory = std::env::temp_dir().join(format!("hoffman-sweep-{}", std::process::id())).to_string_lossy().to_string();
+    sweep.checkpoint = Some((directory.clone(), String::from("checkpoint.json")));
+    let mut batches = 0;
+    let interrupted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        sweep.run(&recipes, |_| { batches += 1; if batches == 2 { panic!("Interrupted.") } })
+    }));
+    assert!(interrupted.is_err());
+    let mut positions = Vec::new();
+    let resumed = sweep.run(&recipes, |report| positions.push(report.position)).unwrap();
+    assert_eq!(positions.first(), Some(&192));
+    assert_eq!(resumed, expected);
+
+    // A changed list of recipes or source, or a damaged file, is not resumed.
+    assert_eq!(sweep.run(&recipes[1..], |_| ()), Err(SweepError::OtherRecipes));
+    let source = sweep.source.clone();
+    sweep.source = TupleSource::Random { n: 3, limit: 60, count: 400, seed: 4 };
+    assert!(matches!(sweep.run(&recipes, |_| ()), Err(SweepError::OtherSource(_))));
+    sweep.source = source;
+    let checkpoint = utils::read_file(&directory, &String::from("checkpoint.json")).unwrap();
+    utils::write_file(&checkpoint.replacen("\"position\": ", "\"position\": -", 1), &directory, &String::from("checkpoint.json")).unwrap();
+    assert!(matches!(sweep.run(&recipes, |_| ()), Err(SweepError::Corrupt(_))));
+    utils::write_file(&checkpoint[..checkpoint.len() / 2].to_string(), &directory, &String::from("checkpoint.json")).unwrap();
+    assert!(matches!(sweep.run(&recipes, |_| ()), Err(SweepError::Corrupt(_))));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn dimension_tuple_validation() {
+    assert_eq!(DimensionTuple::new(vec!()), Err(DimensionTupleError::Empty));
+    assert_eq!(DimensionTuple::new(vec!(0, 5, 6)), Err(DimensionTupleError::NotPositive(0)));
+    assert_eq!(DimensionTuple::new(vec!(5, 4, 6)), Err(DimensionTupleError::NotSorted(0)));
+    assert_eq!(DimensionTuple::new(vec!(3, 5, 6)), Err(DimensionTupleError::Hoffman { sum: 14, bound: 12 }));
+    let error = DimensionTuple::new(vec!(5, 6, 11)).unwrap_err();
+    assert_eq!(error, DimensionTupleError::CollidingSums(vec!((vec!(0, 1), vec!(2)))));
+    assert_eq!(error.to_string(), "Dimension tuple does not have unique sums: a1 + a2 = a3.");
+    assert!(!list_has_unique_sums(&[10, 12, 12]));
+    assert!(list_has_unique_sums(&[10, 12, 13, 14]));
+
+    let dimension_tuple = tuple(vec!(8, 10, 12));
+    assert_eq!(format!("{:?}", dimension_tuple), "[8, 10, 12]");
+    assert_eq!(dimension_tuple.normalised(), tuple(vec!(4, 5, 6)));
+    let order = dimension_tuple.subset_sum_order();
+    assert_eq!(order.len(), 8);
+    assert_eq!((order[0].clone(), order[3].clone(), order[4].clone()), (vec!(), vec!(2), vec!(0, 1)));
+}
+
+#[test]
+fn dimension_tuple_canonical() {
+    assert_eq!(tuple(vec!(20, 23, 24)).canonical(), tuple(vec!(4, 5, 6)));
+    for sides in [vec!(57, 59, 62, 63), vec!(8, 9, 10, 12), vec!(10, 12, 13, 14)] {
+        let dimension_tuple = tuple(sides);
+        let canonical = dimension_tuple.canonical();
+        assert_eq!(canonical.subset_sum_order(), dimension_tuple.subset_sum_order());
+        assert!(canonical.iter().sum::<IntType>() <= dimension_tuple.iter().sum());
+        assert_eq!(canonical.canonical(), canonical);
+        assert!(DimensionTuple::new(canonical.to_vec()).is_ok());
+    }
+    assert_eq!(tuple(vec!(57, 59, 62, 63)).canonical(), tuple(vec!(10, 12, 13, 14)).canonical());
+    assert!(tuple(vec!(8, 9, 10, 12)).canonical() != tuple(vec!(10, 12, 13, 14)).canonical());
+}
+
+#[test]
+fn signatures_undecided_comparisons() {
+    let comparison = |lhs: Vec<usize>, rhs: Vec<usize>| Comparison { lhs, rhs };
+    assert_eq!(undecided_comparisons(3), vec!());
+    assert_eq!(undecided_comparisons(4), vec!(comparison(vec!(0, 3), vec!(1, 2))));
+    assert_eq!(undecided_comparisons(5), vec!(
+        comparison(vec!(0, 3), vec!(1, 2)),
+        comparison(vec!(0, 4), vec!(1, 2)),
+        comparison(vec!(0, 4), vec!(1, 3)),
+        comparison(vec!(0, 4), vec!(2, 3)),
+        comparison(vec!(1, 4), vec!(2, 3))
+    ));
+    assert_eq!(undecided_comparisons(5)[1].to_string(), "a1 + a5 vs a2 + a3");
+}
+
+#[test]
+fn signatures_enumeration() {
+    let table = SignatureTable::enumerate(4, 60);
+    assert_eq!(table.representatives(), vec!(tuple(vec!(8, 9, 10, 12)), tuple(vec!(10, 12, 13, 14))));
+    assert_eq!(signature_str(&table.entries[0].signature), ">");
+    assert_eq!(table.signature(&tuple(vec!(57, 59, 62, 63))), table.entries[1].signature);
+
+    let table = SignatureTable::enumerate(5, 200);
+    assert_eq!(table.entries.len(), 12);
+    for entry in &table.entries {
+        assert_eq!(table.signature(&entry.representative), entry.signature);
+        assert_eq!(entry.representative.canonical(), entry.representative);
+    }
+    let directory = std::env::temp_dir().join(format!("hoffman-signatures-{}", std::process::id())).to_string_lossy().to_string();
+    utils::write_file(&table.to_json(), &directory, &String::from("5d.json")).unwrap();
+    assert_eq!(SignatureTable::load_json(&directory, &String::from("5d")), table);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn simplex_feasibility() {
+    use simplex::*;
+    let mut program = LinearProgram::new(2);
+    program.add_constraint(&[1, 1], Relation::Equal, 3);
+    program.add_constraint(&[1, -1], Relation::GreaterEqual, 2);
+    program.add_constraint(&[0, 2], Relation::GreaterEqual, 1);
+    let point = program.feasible_point().unwrap();
+    let values: Vec<String> = point.iter().map(|v| v.to_string()).collect();
+    assert_eq!(values, vec!("5/2", "1/2"));
+    program.add_constraint(&[-1, 0], Relation::GreaterEqual, -2);
+    assert_eq!(program.feasible_point(), None);
+}
+
+#[test]
+fn signatures_realisability() {
+    let comparisons = undecided_comparisons(5);
+    assert_eq!(realise(5, &comparisons, &[Ordering::Greater, Ordering::Less], false), None);
+    let witness = realise(5, &comparisons, &[Ordering::Greater, Ordering::Greater], true).unwrap();
+    assert!(witness.windows(2).all(|pair| pair[0] < pair[1]) && dimension_tuple::satisfies_hoffman(&witness));
+    assert_eq!(comparisons[1].compare(&witness), Ordering::Greater);
+
+    assert_eq!(realisable_patterns(4, true).len(), 2);
+    let patterns = realisable_patterns(5, true);
+    let table = SignatureTable::enumerate(5, 200);
+    let mut signatures: Vec<Signature> = table.entries.iter().map(|entry| entry.signature.clone()).collect();
+    signatures.sort();
+    assert_eq!(patterns.iter().map(|(pattern, _)| pattern.clone()).collect::<Vec<_>>(), signatures);
+    for (pattern, witness) in &patterns {
+        assert_eq!(&table.signature(witness), pattern);
+        assert!(dimension_tuple::satisfies_hoffman(witness));
+    }
+}
+
+#[test]
+fn recipe_canonical_hash() {
+    let recipe = packing_3d(0);
+    let canonical = recipe.canonical();
+    assert!(recipe.symmetries().iter().all(|symmetry| symmetry.canonical() == canonical));
+    assert!(recipe.symmetries().iter().all(|symmetry| symmetry.canonical_hash() == recipe.canonical_hash()));
+    assert_eq!(recipe.canonical_hash().len(), 16);
+    let other = packing_3d(1);
+    assert!(other.canonical_hash() != recipe.canonical_hash());
+}
+
+#[test]
+fn validity_matrix_of_stored_recipes() {
+    let mut recipes = permuted_3d_packings(3);
+    recipes.push(recipes[0].symmetries()[5].clone());
+    let matrix = validity::ValidityMatrix::new(&recipes, &SignatureTable::enumerate(3, 20));
+    assert_eq!(matrix.rows.iter().map(|row| row.recipes.len()).sum::<usize>(), recipes.len());
+    assert!(matrix.rows[0].recipes.contains(&18));
+    for row in &matrix.rows {
+        let symmetries = recipes[row.recipes[0]].symmetries();
+        assert!(row.recipes.iter().all(|&i| symmetries.contains(&recipes[i])));
+        assert_eq!(row.valid, vec!(row.universal));
+    }
+    let csv = matrix.to_csv();
+    assert!(csv.starts_with("hash,recipes,*,universal\n"));
+    let recipes_str: Vec<String> = matrix.rows[0].recipes.iter().map(|i| i.to_string()).collect();
+    assert!(csv.contains(&format!("{},{},1,1\n", matrix.rows[0].hash, recipes_str.join(" "))));
+
+    let archive = archive::RecipeArchive::open(&String::from("../universal_counterexamples_cubes_4d/res"), &String::from("cube-4d")).unwrap();
+    let cubes: Vec<Recipe> = (0..20).map(|i| archive.get(i).unwrap().unwrap().recipe).collect();
+    let matrix = validity::ValidityMatrix::new(&cubes, &SignatureTable::enumerate(4, 60));
+    assert!(matrix.rows.iter().all(|row| row.valid == vec!(true, true) && row.universal));
+    let json: serde_json::Value = serde_json::from_str(&matrix.to_json()).unwrap();
+    assert_eq!(json["signatures"][1]["representative"], serde_json::json!([10, 12, 13, 14]));
+    assert_eq!(json["recipes"].as_array().unwrap().len(), matrix.rows.len());
+}
+
+#[test]
+fn complete_signature_table() {
+    let table = SignatureTable::complete(4, 60);
+    assert_eq!(table.representatives(), vec!(tuple(vec!(8, 9, 10, 12)), tuple(vec!(10, 12, 13, 14))));
+    let table = SignatureTable::complete(5, 60);
+    assert_eq!(table.entries.len(), realisable_patterns(5, true).len());
+    for entry in &table.entries {
+        assert_eq!(table.signature(&entry.representative), entry.signature);
+        assert!(satisfies_hoffman(&entry.representative));
+    }
+    assert!(table.entries.iter().any(|entry| entry.count == 0));
+    assert_eq!(SignatureTable::from_json(&table.to_json()), table);
+}
+
+fn skewed_3d_packing() -> Recipe {
+    let mut recipe = permuted_3d_packings(1)[0].clone();
+    recipe.map.insert(&vec!(1, 2, 0), vec!(0, 2, 1));
+    recipe
+}
+
+#[test]
+fn validity_region_around_tuple() {
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+    let fraction = |numerator: i64, denominator: i64| BigRational::new(BigInt::from(numerator), BigInt::from(denominator));
+    let recipe = skewed_3d_packing();
+    assert!(permuted_3d_packings(1)[0].validity_region(&[4, 5, 6]).unwrap().nearest_breaking().is_none());
+    let region = recipe.validity_region(&[3, 4, 6]).unwrap();
+    let point: Vec<BigRational> = vec!(3, 4, 6).into_iter().map(|a| fraction(a, 1)).collect();
+    assert!(region.contains(&point));
+    let nearest = region.nearest_breaking().unwrap();
+    assert_eq!(nearest.form.coefficients, vec!(1, -2, 1));
+    assert_eq!(nearest.margin, fraction(1, 4));
+    assert_eq!(region.margin(), fraction(1, 4));
+    let doubled = recipe.validity_region(&[6, 8, 12]).unwrap();
+    assert_eq!(doubled.margin(), fraction(1, 2));
+    // On the facet the bricks touch, beyond it they collide.
+    assert_eq!(recipe.validity_region(&[4, 5, 6]).unwrap().margin(), fraction(0, 1));
+    let violations = recipe.validity_region(&[3, 5, 6]).unwrap_err();
+    let (coord, other) = nearest.collision.clone().unwrap();
+    assert_eq!(violations, vec!(sweep::Violation { coord, other }));
+    assert!(!RecipeBuilder::generate(&recipe, vec!(DimensionTuple::unchecked(vec!(3, 5, 6)))).validate());
+}
+
+#[test]
+fn validity_region_is_valid_inside() {
+    use num_traits::ToPrimitive;
+    let recipe = skewed_3d_packing();
+    let region = recipe.validity_region(&[30, 40, 60]).unwrap();
+    let step = region.margin().to_integer().to_i32().unwrap();
+    assert_eq!(step, 2);
+    for dx in -step..=step {
+        for dy in -step..=step {
+            for dz in -step..=step {
+                let moved = vec!(30 + dx, 40 + dy, 60 + dz);
+                assert!(RecipeBuilder::generate(&recipe, vec!(DimensionTuple::unchecked(moved))).validate());
+            }
+        }
+    }
+}
+
+#[test]
+fn validation_over_other_numbers() {
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+    let large: Vec<i64> = vec!(1000000007, 1000000009, 1000000021);
+    // (1/2, 2/3, 3/4) scaled by 12.
+    let halves = |sides: &[i64]| -> Vec<BigRational> {
+        sides.iter().map(|&a| BigRational::new(BigInt::from(a), BigInt::from(12))).collect()
+    };
+    for recipe in permuted_3d_packings(2) {
+        let valid = RecipeBuilder::generate(&recipe, vec!(tuple(vec!(6, 8, 9)))).validate();
+        assert_eq!(RecipeBuilder::generate_with_sides(&recipe, vec!(large.clone())).validate(), valid);
+        let wide: Vec<i128> = large.iter().map(|&a| a as i128 * 1_000_000_000_000).collect();
+        assert_eq!(RecipeBuilder::generate_with_sides(&recipe, vec!(wide)).validate(), valid);
+        let big: Vec<BigInt> = large.iter().map(|&a| BigInt::from(a).pow(4)).collect();
+        assert_eq!(RecipeBuilder::generate_with_sides(&recipe, vec!(big)).validate(), valid);
+        assert_eq!(RecipeBuilder::generate_with_sides(&recipe, vec!(halves(&[6, 8, 9]))).validate(), valid);
+    }
+    let a = Interval { begin: BigInt::from(0), end: BigInt::from(1) << 100 };
+    assert!(a.intersects(&Interval { begin: BigInt::from(1) << 99, end: BigInt::from(1) << 101 }));
+    assert_eq!(number::sum(&halves(&[6, 4])), BigRational::new(BigInt::from(5), BigInt::from(6)));
+}
+
+#[test]
+fn repeated_sides_merge_orientations() {
+    assert_eq!(DimensionTuple::new(vec!(4, 4, 6)).unwrap_err(), DimensionTupleError::CollidingSums(vec!((vec!(0), vec!(1)))));
+    let degenerate = DimensionTuple::degenerate(vec!(4, 4, 6)).unwrap();
+    assert_eq!(DimensionTuple::degenerate(vec!(4, 3)), Err(DimensionTupleError::NotSorted(0)));
+    assert_eq!(side_classes(std::slice::from_ref(&degenerate)), vec!(0, 0, 2));
+    assert_eq!(side_classes(&[vec!(4, 4, 6), vec!(4, 5, 6)]), vec!(0, 1, 2));
+    assert_eq!(merge_orientation(&[2, 1, 0], &[0, 0, 2]), vec!(2, 0, 1));
+
+    let builder = RecipeBuilder::new(3, 3, vec!(degenerate.clone()));
+    assert_eq!(builder.orientations(), vec!(vec!(0, 1, 2), vec!(0, 2, 1), vec!(2, 0, 1)));
+    assert_eq!(RecipeBuilder::new(3, 3, vec!(tuple(vec!(4, 5, 6)))).orientations().len(), 6);
+
+    // Squares of equal sides fill a square in a grid, which distinct sides may not repeat along a line.
+    let mut squares = RecipeBuilder::with_sides(2, 2, vec!(vec!(3, 3)));
+    let mut distinct = RecipeBuilder::with_sides(2, 2, vec!(vec!(3, 4)));
+    for coord in squares.get_recipe().map.coords().clone() {
+        squares.insert(&coord, &vec!(0, 1));
+        distinct.insert(&coord, &vec!(0, 1));
+        assert!(squares.is_valid(&coord));
+    }
+    assert!(!distinct.satisfies_line_criterion(&vec!(1, 1)));
+
+    let recipe = permuted_3d_packings(1)[0].clone();
+    let relabelled = recipe.pre_permute(&vec!(1, 0, 2));
+    assert_eq!(Recipe::find_unique(vec!(recipe.clone(), relabelled.clone())).len(), 2);
+    let unique = Recipe::find_unique_for(vec!(recipe.clone(), relabelled), &[degenerate]);
+    assert!(unique == vec!(recipe.merged(&[0, 0, 2])));
+}
+
+#[test]
+fn zero_sides_degenerate_packings() {
+    assert!(!Interval { begin: 5, end: 5 }.overlaps(&Interval { begin: 2, end: 7 }));
+    assert!(Interval { begin: 5, end: 5 }.intersects(&Interval { begin: 2, end: 7 }));
+    assert!(Interval { begin: 2, end: 7 }.overlaps(&Interval { begin: 5, end: 9 }));
+    assert!(DimensionTuple::new(vec!(0, 5, 6)).is_err());
+    let limit = DimensionTuple::with_zero_sides(vec!(0, 5, 6)).unwrap();
+    assert_eq!(DimensionTuple::with_zero_sides(vec!(0, 0)), Err(DimensionTupleError::AllZero));
+    assert_eq!(DimensionTuple::with_zero_sides(vec!()), Err(DimensionTupleError::Empty));
+
+    let recipes = permuted_3d_packings(2);
+    let valid: Vec<&Recipe> = recipes.iter().filter(|recipe| {
+        RecipeBuilder::generate(recipe, vec!(tuple(vec!(4, 5, 6)))).validate()
+    }).collect();
+    assert!(!valid.is_empty());
+    for recipe in valid {
+        let builder = RecipeBuilder::generate(recipe, vec!(limit.clone()));
+        // Every brick is flat, so nothing overlaps or forms a sharp corner.
+        assert!(builder.validate());
+        assert!(recipe.map.coords().iter().all(|coord| !builder.is_sharp_corner(coord)));
+        // With as many sides as axes no brick keeps its volume.
+        assert_eq!(builder.induced_bricks(0).map(|induced| induced.boxes.len()), Some(0));
+    }
+
+    let layer: Recipe = "bc cb ca\ncb ab ab\nab ab ab".parse().unwrap();
+    assert!(RecipeBuilder::generate(&layer, vec!(tuple(vec!(4, 5, 6)))).validate());
+    assert!(RecipeBuilder::generate(&layer, vec!(tuple(vec!(4, 5, 6)))).induced_bricks(0).is_none());
+    let limit = DimensionTuple::with_zero_sides(vec!(0, 5, 6)).unwrap();
+    let induced = RecipeBuilder::generate(&layer, vec!(limit)).induced_bricks(0).unwrap();
+    assert_eq!(induced.dimension_tuple, vec!(5, 6));
+    let cells: Vec<(Coord, Orientation)> = induced.boxes.iter().map(|placed| (placed.coord.clone(), placed.orientation.clone())).collect();
+    assert_eq!(cells, vec!((vec!(0, 0), vec!(0, 1)), (vec!(0, 1), vec!(1, 0)), (vec!(1, 0), vec!(1, 0))));
+    let mut recipe = Recipe::new(2, 2);
+    for (coord, orientation) in &cells {
+        recipe.map.insert(coord, orientation.clone());
+    }
+    let builder = RecipeBuilder::generate(&recipe, vec!(tuple(vec!(5, 6))));
+    assert!(builder.validate());
+    for placed in &induced.boxes {
+        assert_eq!(builder.get_packing(0).get(&placed.coord), Some(&placed.hyper_rectangle));
+    }
+    // The empty cell takes a fourth brick, leaving only the gap of distinct sides.
+    recipe.map.insert(&vec!(1, 1), vec!(0, 1));
+    let builder = RecipeBuilder::generate(&recipe, vec!(tuple(vec!(5, 6))));
+    assert!(builder.validate());
+    assert_eq!(builder.verify_packing(0).map(|certificate| certificate.gap), Ok(11 * 11 - 4 * 30));
+}
+
+#[test]
+fn global_packing_verifier() {
+    let recipes = permuted_3d_packings(2);
+    let (mut valid, mut invalid) = (0, 0);
+    for recipe in &recipes {
+        let builder = RecipeBuilder::generate(recipe, vec!(tuple(vec!(4, 5, 6))));
+        match builder.verify_packing(0) {
+            Ok(certificate) => {
+                assert!(builder.validate());
+                assert_eq!(certificate.box_count, 27);
+                assert_eq!((certificate.volume, certificate.cube_volume), (27 * 120, 15 * 15 * 15));
+                assert_eq!(certificate.gap, 15 * 15 * 15 - 27 * 120);
+                valid += 1;
+            },
+            Err(verify::VerificationError::Overlap(coord, other)) => {
+                assert!(!builder.validate());
+                assert!(builder.has_overlaps(&coord) && builder.has_overlaps(&other));
+                invalid += 1;
+            },
+            Err(error) => panic!("{}", error)
+        }
+    }
+    assert!(valid > 0 && invalid > 0);
+
+    let cube = |begin: [i64; 2], end: [i64; 2]| -> HyperRectangle<i64> {
+        begin.iter().zip(end.iter()).map(|(&begin, &end)| Interval { begin, end }).collect()
+    };
+    // Far apart on the grid, overlapping in space.
+    let boxes = vec!(
+        (vec!(0, 0), cube([0, 0], [2, 2])),
+        (vec!(5, 5), cube([3, 3], [4, 4])),
+        (vec!(9, 9), cube([1, 1], [3, 3])),
+        (vec!(1, 1), cube([2, 0], [2, 4]))
+    );
+    assert_eq!(verify::verify_packing(&boxes, &4), Err(verify::VerificationError::Overlap(vec!(0, 0), vec!(9, 9))));
+    assert_eq!(verify::verify_packing(&boxes[..2], &3), Err(verify::VerificationError::OutsideCube(vec!(5, 5))));
+    let certificate = verify::verify_packing(&[boxes[0].clone(), boxes[1].clone(), boxes[3].clone()], &4).unwrap();
+    assert_eq!((certificate.volume, certificate.cube_volume, certificate.gap), (5, 16, 11));
+}
+
+#[test]
+fn box_list_round_trip_and_inference() {
+    let recipe = permuted_3d_packings(1)[0].clone();
+    let builder = RecipeBuilder::generate(&recipe, vec!(tuple(vec!(4, 5, 6))));
+    let list = builder.box_list(0);
+    assert_eq!(list.boxes.len(), 27);
+    assert_eq!(boxes::BoxList::from_json(&list.to_json()), Ok(list.clone()));
+    assert_eq!(boxes::BoxList::from_csv(&list.to_csv(), &[4, 5, 6]), Ok(list.clone()));
+    assert!(list.to_csv().starts_with("coord1,coord2,coord3,orientation1,orientation2,orientation3,begin1,end1,"));
+    assert!(list.infer_recipe().unwrap() == recipe);
+
+    // Positions alone, shifted and in another order, as from a CAD drawing.
+    let mut csv = String::from("end1,begin1,end2,begin2,end3,begin3,label\n");
+    for placed in list.boxes.iter().rev() {
+        let bounds: Vec<String> = placed.hyper_rectangle.iter().map(|interval| {
+            format!("{},{}", interval.end + 100, interval.begin + 100)
+        }).collect();
+        csv.push_str(&format!("{},0\n", bounds.join(",")));
+    }
+    let imported = boxes::BoxList::from_csv(&csv, &[4, 5, 6]).unwrap();
+    assert!(imported.boxes.iter().all(|placed| placed.coord.is_empty()));
+    assert!(imported.infer_recipe().unwrap() == recipe);
+
+    let mut missing = list.clone();
+    missing.boxes.pop();
+    assert_eq!(missing.infer_recipe().err(), Some(boxes::BoxListError::Count(26)));
+    let mut moved = list.clone();
+    moved.boxes[13].hyper_rectangle[0].begin += 1;
+    assert_eq!(moved.infer_recipe().err(), Some(boxes::BoxListError::Missing(list.boxes[13].coord.clone())));
+    let mut stretched = list.clone();
+    stretched.boxes[0].hyper_rectangle[0].end += 1;
+    assert!(matches!(stretched.infer_recipe().err(), Some(boxes::BoxListError::Width(_, _))));
+    let mut doubled = list.clone();
+    doubled.boxes[26] = doubled.boxes[0].clone();
+    assert_eq!(doubled.infer_recipe().err(), Some(boxes::BoxListError::Ambiguous(vec!(0, 0, 0))));
+    let mut flattened = list.clone();
+    flattened.boxes[5].hyper_rectangle.pop();
+    assert_eq!(flattened.infer_recipe().err(), Some(boxes::BoxListError::Dimension(5)));
+    // A box flat along the first axis is also where the next cell along that axis starts.
+    let bounds = |begin: [IntType; 2], end: [IntType; 2]| boxes::PlacedBox {
+        coord: Vec::new(),
+        orientation: Vec::new(),
+        hyper_rectangle: begin.iter().zip(end.iter()).map(|(&begin, &end)| Interval { begin, end }).collect()
+    };
+    let flat = boxes::BoxList { dimension_tuple: vec!(0, 3), boxes: vec!(
+        bounds([0, 0], [0, 3]), bounds([0, 3], [3, 3]), bounds([3, 0], [3, 3]), bounds([3, 3], [6, 3])
+    ) };
+    assert_eq!(flat.infer_recipe().err(), Some(boxes::BoxListError::Reused(vec!(1, 0))));
+    assert!(boxes::BoxList::from_csv("coord1\n1\n", &[4, 5, 6]).is_err());
+    assert!(boxes::BoxList::from_csv("coord1,begin1,end1\n-1,0,4\n", &[4, 5, 6]).is_err());
+    assert!(boxes::BoxList::from_json(&list.to_json().replacen("\"end\": [", "\"end\": [4294967296, ", 1)).is_err());
+    assert!(matches!(boxes::BoxList::from_json(&list.to_json().replacen("\"end\": [", "\"end\": [0, ", 1)), Err(boxes::BoxListError::Parse(_))));
+    assert!(matches!(boxes::BoxList::load_json(&String::from("exports/none"), &String::from("none")), Err(boxes::BoxListError::Read(_))));
+}
+
+#[test]
+fn binary_recipe_files() {
+    use binary::*;
+    for &(n, m) in &[(3, 3), (4, 4), (4, 2), (5, 3)] {
+        let indices: Vec<usize> = (0..n).collect();
+        let arrangements = permutations(&indices, m);
+        for (rank, orientation) in arrangements.iter().enumerate() {
+            assert_eq!(orientation_rank(orientation, n), rank as u64);
+            assert_eq!(&orientation_unrank(rank as u64, n, m), orientation);
+        }
+    }
+    let bits = |n, m| BinaryHeader::new(n, m, vec!()).bits();
+    assert_eq!((bits(3, 3), bits(4, 4), bits(5, 5), bits(4, 2)), (3, 5, 7, 4));
+
+    let recipes = permuted_3d_packings(2);
+    let mut kernel = Recipe::new(4, 2);
+    kernel.map.insert(&vec!(1, 2), vec!(3, 0));
+    let directory = std::env::temp_dir().join(format!("hoffman-binary-{}", std::process::id())).to_string_lossy().to_string();
+    let name = String::from("3d");
+    let header = BinaryHeader::new(3, 3, vec!(vec!(4, 5, 6), vec!(5, 6, 7)));
+    assert_eq!(header.record_size(), 11);
+    let mut writer = BinaryWriter::create(&directory, &name, header.clone()).unwrap();
+    for recipe in &recipes[..5] {
+        writer.push(recipe).unwrap();
+    }
+    drop(writer);
+    let mut writer = BinaryWriter::append(&directory, &name).unwrap();
+    assert_eq!(writer.header(), &header);
+    for recipe in &recipes[5..] {
+        writer.push(recipe).unwrap();
+    }
+    let archive = BinaryArchive::open(&directory, &name).unwrap();
+    assert_eq!(archive.header(), &header);
+    assert_eq!(archive.len(), recipes.len());
+    assert!(archive.get(7).unwrap() == recipes[7] && archive.get(recipes.len()).is_none());
+    assert!(archive.iter().zip(recipes.iter()).all(|(a, b)| &a == b));
+
+    let kernels = BinaryHeader::new(4, 2, vec!());
+    assert!(kernels.decode(&kernels.encode(&kernel)) == kernel);
+
+    for (i, recipe) in recipes.iter().enumerate() {
+        utils::write_file(&recipe.to_json(), &directory, &format!("{}.json", i)).unwrap();
+    }
+    let file_names: Vec<String> = (0..recipes.len()).map(|i| i.to_string()).collect();
+    let mut writer = BinaryWriter::create(&directory, &String::from("converted"), header).unwrap();
+    writer.push_json_files(&directory, &file_names).unwrap();
+    assert!(writer.push_json_files(&directory, &[String::from("missing")]).is_err());
+    let converted = BinaryArchive::open(&directory, &String::from("converted")).unwrap();
+    assert!(converted.iter().zip(recipes.iter()).all(|(a, b)| &a == b));
+    utils::write_file(&String::from("not a recipe file"), &directory, &String::from("bad.bin")).unwrap();
+    assert!(BinaryArchive::open(&directory, &String::from("bad")).is_err());
+    let header_bytes = |n: u32, m: u32, count: u32, sides: &[i64]| {
+        let mut bytes = b"HOFFMAN1".to_vec();
+        for word in &[n, m, count] {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        for side in sides {
+            bytes.extend_from_slice(&side.to_le_bytes());
+        }
+        bytes
+    };
+    let headers = vec!(
+        header_bytes(0, 0, 0, &[]), header_bytes(3, 0, 0, &[]), header_bytes(2, 3, 0, &[]),
+        header_bytes(1000, 1000, 0, &[]), header_bytes(u32::MAX, 1, u32::MAX, &[]),
+        header_bytes(1, 1, 1, &[1 << 40])
+    );
+    for bytes in &headers {
+        utils::write_bytes(bytes, &directory, &String::from("bad.bin")).unwrap();
+        assert_eq!(BinaryArchive::open(&directory, &String::from("bad")).err().map(|error| error.kind()), Some(std::io::ErrorKind::InvalidData));
+    }
+    utils::write_bytes(&header_bytes(1, 1, 1, &[4]), &directory, &String::from("bad.bin")).unwrap();
+    assert_eq!(BinaryArchive::open(&directory, &String::from("bad")).unwrap().header().dimension_tuples, vec!(vec!(4)));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn recipe_archive_dedupes_and_looks_up() {
+    use archive::*;
+    let directory = std::env::temp_dir().join(format!("hoffman-archive-{}", std::process::id())).to_string_lossy().to_string();
+    let name = String::from("3d");
+    let recipes: Vec<Recipe> = (0..3).map(packing_3d).collect();
+    let mut archive = RecipeArchive::create(&directory, &name).unwrap();
+    assert!(archive.is_empty());
+    for (i, recipe) in recipes.iter().enumerate() {
+        assert!(archive.insert(&format!("packing \"{}\"", i), recipe).unwrap());
+    }
+    assert!(!archive.insert("symmetry", &recipes[1].symmetries()[3]).unwrap());
+    archive.append("duplicate", &recipes[2]).unwrap();
+    assert_eq!(archive.len(), 4);
+
+    let archive = RecipeArchive::open(&directory, &name).unwrap();
+    assert_eq!(archive.len(), 4);
+    let entry = archive.lookup(&recipes[1].symmetries()[5]).unwrap().unwrap();
+    assert!(entry.name == "packing \"1\"" && entry.recipe == recipes[1]);
+    assert_eq!(archive.find(&recipes[2].canonical_hash()).unwrap().len(), 2);
+    assert!(archive.get(4).unwrap().is_none());
+    let names: Vec<String> = archive.iter().unwrap().map(|entry| entry.unwrap().name).collect();
+    assert_eq!(names, vec!("packing \"0\"", "packing \"1\"", "packing \"2\"", "duplicate"));
+
+    let loose = format!("{}/loose", directory);
+    for i in &[10, 2, 1] {
+        utils::write_file(&recipes[i % 3].to_json(), &loose, &format!("packing {}.json", i)).unwrap();
+    }
+    utils::write_file(&String::from("{ \"dimension_tuple\": [], \"boxes\": [] }"), &loose, &String::from("boxes.json")).unwrap();
+    assert_eq!(RecipeArchive::open(&directory, &String::from("imported")).err().map(|error| error.kind()), Some(std::io::ErrorKind::NotFound));
+    let mut imported = RecipeArchive::open_or_create(&directory, &String::from("imported")).unwrap();
+    assert_eq!(imported.import_directory(&loose).unwrap(), 2);
+    let names: Vec<String> = imported.iter().unwrap().map(|entry| entry.unwrap().name).collect();
+    assert_eq!(names, vec!("packing 1", "packing 2"));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn solution_sinks_compose() {
+    use sink::*;
+    let recipes = permuted_3d_packings(3);
+    let mut found = Count::new();
+    let mut unique = Vec::new();
+    let mut sample = Sample::new(4, 7);
+    {
+        let mut chain = (&mut found, (Dedupe::new(), (&mut unique, &mut sample)));
+        let kept: Vec<bool> = recipes.iter().map(|recipe| chain.push(recipe)).collect();
+        assert_eq!(kept.iter().filter(|&&kept| kept).count(), (chain.1).0.len());
+    }
+    assert_eq!(found.count, recipes.len());
+    assert!(unique == Recipe::find_unique(recipes.clone()));
+    assert_eq!(sample.recipes.len(), 4);
+    assert!(sample.recipes.iter().all(|recipe| unique.contains(recipe)));
+
+    let directory = std::env::temp_dir().join(format!("hoffman-sink-{}", std::process::id())).to_string_lossy().to_string();
+    let archive = archive::RecipeArchive::create(&directory, &String::from("3d")).unwrap();
+    let mut written = (Dedupe::new(), ArchiveSink::new(archive, "packing"));
+    for recipe in &recipes {
+        written.push(recipe);
+    }
+    let names: Vec<String> = written.1.archive.iter().unwrap().map(|entry| entry.unwrap().name).collect();
+    assert_eq!(names.len(), unique.len());
+    assert_eq!(names.last().unwrap(), &format!("packing-{}", unique.len()));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn npy_recipes_and_packings() {
+    use npy::*;
+    let recipe = packing_3d(2);
+    let array = NpyArray::from_recipe(&recipe);
+    assert_eq!(array.shape, vec!(27, 3));
+    let bytes = array.to_bytes();
+    assert_eq!(bytes.len() - 27 * 3 * 8, 128);
+    assert_eq!(bytes[127], b'\n');
+    assert!(bytes.starts_with(b"\x93NUMPY\x01\x00"));
+    assert!(String::from_utf8_lossy(&bytes[10..]).starts_with("{'descr': '<i8', 'fortran_order': False, 'shape': (27, 3), }"));
+    assert_eq!(NpyArray::from_bytes(&bytes), Ok(array.clone()));
+    assert!(array.to_recipe().unwrap() == recipe);
+
+    let mut kernel = Recipe::new(4, 2);
+    kernel.map.insert(&vec!(1, 2), vec!(3, 0));
+    let array = NpyArray::from_recipe(&kernel);
+    assert_eq!(&array.data[10..16], &[-1, -1, 3, 0, -1, -1]);
+    assert!(array.to_recipe().unwrap() == kernel);
+
+    let recipe_builder = RecipeBuilder::generate(&recipe, vec!(tuple(vec!(4, 5, 6))));
+    let packing = NpyArray::from_packing(&recipe_builder, 0);
+    assert_eq!(packing.shape, vec!(27, 3, 2));
+    assert_eq!(&packing.data[..6], &[0, 4, 0, 5, 0, 6]);
+    assert!(NpyArray::from_bytes(&packing.to_bytes()).unwrap().to_recipe().unwrap() == recipe);
+    let partial = NpyArray::from_packing(&RecipeBuilder::generate(&kernel, vec!(tuple(vec!(8, 9, 10, 12)))), 0);
+    assert_eq!(&partial.data[20..24], &[-1, -1, -1, -1]);
+    assert!(partial.data[24..28].iter().all(|&bound| bound >= 0) && partial.data[28..].iter().all(|&bound| bound == -1));
+    assert_eq!(partial.to_recipe().err(), Some(NpyError::Packing(boxes::BoxListError::Count(1))));
+
+    // Version 2.0 with unsigned bytes, as NumPy writes for large headers.
+    let header = "{'descr': '|u1', 'fortran_order': False, 'shape': (4, 2), }\n";
+    let mut bytes = b"\x93NUMPY\x02\x00".to_vec();
+    bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(&[0, 1, 1, 0, 1, 0, 0, 1]);
+    let array = NpyArray::from_bytes(&bytes).unwrap();
+    assert_eq!(array, NpyArray::new(vec!(4, 2), vec!(0, 1, 1, 0, 1, 0, 0, 1)));
+    assert!(array.to_recipe().unwrap().map.get(&vec!(1, 0)) == Some(&vec!(1, 0)));
+    assert_eq!(NpyArray::new(vec!(5, 2), vec!(0; 10)).to_recipe().err(), Some(NpyError::Shape(vec!(5, 2))));
+    assert!(NpyArray::from_bytes(b"not an array").is_err());
+
+    let directory = std::env::temp_dir().join(format!("hoffman-npy-{}", std::process::id())).to_string_lossy().to_string();
+    utils::write_bytes(&NpyArray::from_recipe(&recipe).to_bytes(), &directory, &String::from("recipe.npy")).unwrap();
+    assert!(Recipe::load_npy(&directory, &String::from("recipe")).unwrap() == recipe);
+    assert!(matches!(Recipe::load_npy(&directory, &String::from("missing")), Err(NpyError::Read(_))));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn recipe_text_notation() {
+    let mut kernel = Recipe::new(4, 2);
+    kernel.map.insert(&vec!(1, 2), vec!(3, 0));
+    kernel.map.insert(&vec!(2, 1), vec!(0, 3));
+    assert_eq!(kernel.to_string(), ".. .. .. ..\n.. .. da ..\n.. ad .. ..\n.. .. .. ..");
+    assert_eq!(kernel.to_string().parse::<Recipe>(), Ok(kernel.clone()));
+
+    let recipe = packing_3d(0);
+    let text = recipe.to_string();
+    assert_eq!(text.split("\n\n").count(), 3);
+    assert!(text.lines().all(|line| line.is_empty() || line.split(' ').count() == 3));
+    assert_eq!(text.parse::<Recipe>(), Ok(recipe.clone()));
+    let padded = format!("\n  {}\n\n\n", text.replace("\n\n", "\n\n\n"));
+    assert_eq!(padded.parse::<Recipe>(), Ok(recipe.clone()));
+    assert!(format!("{:?}", recipe).starts_with("Recipe (n = 3, m = 3):\nabc "));
+    assert_eq!(format!("{:?}", kernel.map), "{[1, 2]: [3, 0], [2, 1]: [0, 3]}");
+
+    assert_eq!("ab ba\nba ab".parse::<Recipe>().map(|recipe| recipe.map.get(&vec!(1, 0)).cloned()), Ok(Some(vec!(1, 0))));
+    assert_eq!("ab ba\nba aa".parse::<Recipe>(), Err(ParseRecipeError::Word(String::from("aa"))));
+    assert_eq!("ab bc\nba ab".parse::<Recipe>(), Err(ParseRecipeError::Word(String::from("bc"))));
+    assert!(matches!("ab ba ab\nba ab ba".parse::<Recipe>(), Err(ParseRecipeError::Shape(_))));
+    assert!(matches!("ab ba\nba".parse::<Recipe>(), Err(ParseRecipeError::Shape(_))));
+    assert!(matches!("".parse::<Recipe>(), Err(ParseRecipeError::Shape(_))));
+}
+
+#[test]
+fn plot_slices_of_any_dimension() {
+    use plot::*;
+    let mut recipe = Recipe::new(5, 3);
+    for coord in recipe.map.coords().clone() {
+        let s: usize = coord.iter().sum();
+        recipe.map.insert(&coord, vec!(s % 5, (s + 1) % 5, (s + 3) % 5));
+    }
+    let dimension_tuple = vec!(3, 5, 6, 7, 10);
+    let figure = plot_slices(&recipe, &dimension_tuple, &SliceOptions::default());
+    assert_eq!((figure.rows, figure.columns, figure.plots.len()), (3, 5, 15));
+    assert_eq!(figure.plots[0].name, Some(String::from("yz-square at x=1")));
+    assert!(figure.plots.iter().all(|plot| plot.rectangles.len() == 25));
+    let svg = figure.to_svg();
+    assert!(svg.contains(&palette(10)[9].0));
+    assert!(figure.to_tikz().contains(&palette(10)[9].1));
+
+    let options = SliceOptions { axes: Some((2, 0)), labels: None, columns: Some(2) };
+    let figure = plot_slices(&recipe, &dimension_tuple, &options);
+    assert_eq!((figure.rows, figure.columns, figure.plots.len()), (3, 2, 6));
+    assert_eq!(figure.plots[4].name, Some(String::from("zx-square at y=5")));
+    assert!(figure.plots[5].name.is_none() && figure.plots[5].rectangles.is_empty());
+    let brick = &figure.plots[0].rectangles[1];
+    assert_eq!((brick.x, brick.y, brick.width, brick.height), (0, 3, 10, 5));
+
+    let colors = palette(40);
+    assert_eq!(colors[0].0, "rgb(236, 31, 38)");
+    let mut distinct: Vec<&String> = colors.iter().map(|(svg, _)| svg).collect();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 40);
+}
+
+#[test]
+fn isometric_rendering_orders_faces() {
+    use plot::*;
+    let recipe = packing_3d(0);
+    let drawing = render_isometric(&recipe, &[4, 5, 6], &IsometricOptions::default());
+    assert_eq!(drawing.faces.len(), 27 * 3);
+    // Seen along the diagonal, the brick at the corner nearest the viewer comes last.
+    let elevation = (0.5f64).sqrt().atan();
+    let corner = (0.0, 15.0 * (elevation.cos() - elevation.sin() * 2f64.sqrt()));
+    let near = |point: &(f64, f64)| (point.0 - corner.0).abs() < 1e-9 && (point.1 - corner.1).abs() < 1e-9;
+    assert!(drawing.faces[78..].iter().all(|face| face.points.iter().any(near)));
+    let svg = drawing.to_svg();
+    assert_eq!(svg.matches("<polygon").count(), 81);
+    assert_eq!(drawing.to_tikz().matches("\\filldraw").count(), 81);
+
+    // Exploding moves the nearest brick away from the others.
+    let options = IsometricOptions { explode: 0.5, ..IsometricOptions::default() };
+    let exploded = render_isometric(&recipe, &[4, 5, 6], &options);
+    let top = |drawing: &IsometricDrawing| drawing.faces.iter().flat_map(|face| face.points.iter().map(|p| p.1)).fold(f64::MIN, f64::max);
+    assert!(top(&exploded) > top(&drawing));
+
+    // A three-dimensional slice of a tesseract packing, seen from another side.
+    let mut tesseract = Recipe::new(4, 4);
+    for coord in tesseract.map.coords().clone() {
+        let s: usize = coord.iter().sum();
+        tesseract.map.insert(&coord, (0..4).map(|i| (s + i) % 4).collect());
+    }
+    let options = IsometricOptions { axes: [3, 1, 0], levels: vec!((2, 1)), azimuth: 200.0, elevation: 20.0, ..IsometricOptions::default() };
+    let slice = render_isometric(&tesseract, &[1, 2, 3, 4], &options);
+    assert_eq!(slice.faces.len(), 64 * 3);
+}
+
+#[test]
+fn mesh_export_formats() {
+    use mesh::*;
+    let recipe = packing_3d(0);
+    let recipe_builder = RecipeBuilder::generate(&recipe, vec!(tuple(vec!(4, 5, 6))));
+    let mesh = Mesh::new(&recipe_builder, 0, &MeshOptions::default());
+    assert_eq!(mesh.boxes.len(), 27);
+    let volume: f64 = mesh.boxes.iter().map(|b| (0..3).map(|i| b.max[i] - b.min[i]).product::<f64>()).sum();
+    assert_eq!(volume, 27.0 * 4.0 * 5.0 * 6.0);
+    assert!(mesh.boxes.iter().zip(recipe.map.coords()).all(|(b, coord)| {
+        b.class as u64 == binary::orientation_rank(recipe.map.get(coord).unwrap(), 3)
+    }));
+    let mut classes: Vec<usize> = mesh.boxes.iter().map(|b| b.class).collect();
+    classes.sort();
+    classes.dedup();
+
+    let options = MeshOptions { scale: 2.0, gap: 1.0, shrink: 0.1, ..MeshOptions::default() };
+    let spaced = Mesh::new(&recipe_builder, 0, &options);
+    assert_eq!(spaced.boxes[26].name, "box 2 2 2");
+    assert!(spaced.boxes[26].max.iter().all(|&v| (v - 33.8).abs() < 1e-9));
+    assert!(spaced.boxes[0].min.iter().all(|&v| (v - 0.2).abs() < 1e-9));
+
+    let stl = mesh.to_stl();
+    assert_eq!((stl.matches("endsolid").count(), stl.matches("facet normal").count()), (27, 324));
+    let bytes = mesh.to_binary_stl();
+    assert_eq!(bytes.len(), 84 + 50 * 324);
+    let float = |offset: usize| f32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+    for triangle in 0..324 {
+        let at = 84 + 50 * triangle;
+        let vector = |k: usize| [0, 1, 2].map(|i| float(at + 12 * k + 4 * i));
+        let (normal, a, b, c) = (vector(0), vector(1), vector(2), vector(3));
+        let (u, v) = ([0, 1, 2].map(|i| b[i] - a[i]), [0, 1, 2].map(|i| c[i] - a[i]));
+        let cross = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        assert!((0..3).map(|i| cross[i] * normal[i]).sum::<f32>() > 0.0);
+        assert!(bytes[at + 49] & 0x80 != 0);
+    }
+
+    let (obj, mtl) = mesh.to_obj("packing");
+    assert!(obj.starts_with("mtllib packing.mtl\no box_0_0_0\nusemtl class_"));
+    assert_eq!((obj.matches("\nv ").count(), obj.matches("\nf ").count()), (216, 162));
+    assert!(obj.ends_with(&format!("f {} {} {} {}\n", 216 - 3, 216 - 2, 216, 216 - 1)));
+    assert_eq!(mtl.matches("newmtl").count(), classes.len());
+
+    let gltf: serde_json::Value = serde_json::from_str(&mesh.to_gltf()).unwrap();
+    assert_eq!(gltf["asset"]["version"], "2.0");
+    assert_eq!(gltf["nodes"].as_array().unwrap().len(), 27);
+    assert_eq!(gltf["accessors"].as_array().unwrap().len(), 29);
+    assert_eq!(gltf["materials"].as_array().unwrap().len(), classes.len());
+    let length = gltf["buffers"][0]["byteLength"].as_u64().unwrap() as usize;
+    assert_eq!(length, 72 + 288 + 27 * 288);
+    let uri = gltf["buffers"][0]["uri"].as_str().unwrap();
+    assert!(uri.starts_with("data:application/octet-stream;base64,AAABAAIA"));
+    assert_eq!(uri.len() - "data:application/octet-stream;base64,".len(), length.div_ceil(3) * 4);
+    assert!((0..3).all(|i| gltf["accessors"][28]["max"][i].as_f64() == Some(15.0)));
+
+    let mut tesseract = Recipe::new(4, 4);
+    for coord in tesseract.map.coords().clone() {
+        let s: usize = coord.iter().sum();
+        tesseract.map.insert(&coord, (0..4).map(|i| (s + i) % 4).collect());
+    }
+    let options = MeshOptions { axes: [3, 0, 1], levels: vec!((2, 3)), ..MeshOptions::default() };
+    let slice = Mesh::new(&RecipeBuilder::generate_with_sides(&tesseract, vec!(vec!(1, 2, 3, 4))), 0, &options);
+    assert_eq!(slice.boxes.len(), 64);
+    assert!(slice.boxes.iter().all(|b| b.name.split(' ').nth(3) == Some("3")));
+}
+
+#[test]
+fn puzzle_scad_script() {
+    use scad::*;
+    let puzzle = Puzzle::new(&packing_3d(0), &tuple(vec!(4, 5, 6)), PuzzleOptions::default());
+    assert_eq!((puzzle.bricks.len(), puzzle.side()), (27, 15));
+    let parts = puzzle.parts_list();
+    assert_eq!(parts.iter().map(|(_, coords)| coords.len()).sum::<usize>(), 27);
+    assert!(parts.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    let order = puzzle.assembly_order().unwrap();
+    let mut placed: Vec<&scad::Brick> = Vec::new();
+    for coord in &order {
+        let brick = puzzle.bricks.iter().find(|brick| &brick.coord == coord).unwrap();
+        let meets = |other: &scad::Brick| (0..2).all(|i| other.hyper_rectangle[i].overlaps(&brick.hyper_rectangle[i]));
+        let (bottom, top) = (brick.hyper_rectangle[2].begin, brick.hyper_rectangle[2].end);
+        assert!(bottom == 0 || placed.iter().any(|other| other.hyper_rectangle[2].end == bottom && meets(other)));
+        assert!(!placed.iter().any(|other| other.hyper_rectangle[2].begin >= top && meets(other)));
+        placed.push(brick);
+    }
+    assert_eq!(placed.len(), 27);
+
+    let script = puzzle.to_scad();
+    assert_eq!(script.matches(" brick([").count(), 2 * 27);
+    assert!(script.contains("inner = 15 * scale + clearance;\n"));
+    assert!(script.contains("pitch = 6 * scale + spacing;\n"));
+    assert!(script.contains("//    1. (0, 0, 0) "));
+    assert!(script.contains("//   27. "));
+    assert_eq!(script.matches('{').count(), script.matches('}').count());
+
+    let floating = |begin: IntType| scad::Brick {
+        coord: vec!(0, 0, begin as usize),
+        orientation: vec!(0, 1, 2),
+        hyper_rectangle: vec!(Interval { begin: 0, end: 4 }, Interval { begin: 0, end: 5 }, Interval { begin, end: begin + 6 })
+    };
+    let hanging = Puzzle { bricks: vec!(floating(0), floating(7)), ..puzzle };
+    assert_eq!(hanging.assembly_order(), Err(vec!(vec!(0, 0, 7))));
+}
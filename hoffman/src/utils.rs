@@ -2,10 +2,9 @@ use std::io::prelude::*;
 use std::io::{BufReader, Error};
 use std::fs::{File, create_dir_all};
 use std::path::Path;
-use std::collections::HashSet;
 
 use combinatorics::*;
-use super::{Coord, Shape, IntType};
+use super::{Coord, Shape, IntType, dimension_tuple};
 
 pub fn make_coords(shape: &Shape) -> Vec<Coord> {
     let axes: Vec<Vec<usize>> = shape.iter().map(|&size| {
@@ -15,13 +14,13 @@ pub fn make_coords(shape: &Shape) -> Vec<Coord> {
 }
 
 pub fn list_has_unique_sums(list: &[IntType]) -> bool {
-    let mut tuples: Vec<Vec<IntType>> = Vec::new();
-    for i in 0..list.len() + 1 {
-        tuples.extend(combinations(list, i));
-    }
-    let tuple_sums: Vec<IntType> = tuples.iter().map(|a| a.iter().sum()).collect();
-    let unique_sums: HashSet<IntType> = tuple_sums.iter().cloned().collect();
-    tuple_sums.len() == unique_sums.len()
+    dimension_tuple::colliding_subsets(list).is_empty()
+}
+
+/// Neighbours as considered by `RecipeBuilder::has_overlaps`, except those on a common line.
+pub fn are_diagonal_neighbours(a: &Coord, b: &Coord) -> bool {
+    let distances: Vec<usize> = a.iter().zip(b.iter()).map(|(&x, &y)| x.abs_diff(y)).collect();
+    distances.iter().all(|&v| v <= 1) && distances.iter().filter(|&&v| v == 1).count() >= 2
 }
 
 pub fn list_except<T: PartialEq + Clone>(list: &[T], exclude: &[T]) -> Vec<T> {
@@ -59,3 +58,15 @@ pub fn read_file(directory: &String, file_name: &String) -> Result<String, Error
     buf_reader.read_to_string(&mut contents)?;
     Ok(contents)
 }
+
+pub fn write_bytes(contents: &[u8], directory: &String, file_name: &String) -> Result<(), Error> {
+    create_dir_all(directory)?;
+    let mut file = File::create(Path::new(directory).join(file_name))?;
+    file.write_all(contents)
+}
+
+pub fn read_bytes(directory: &String, file_name: &String) -> Result<Vec<u8>, Error> {
+    let mut contents = Vec::new();
+    File::open(Path::new(directory).join(file_name))?.read_to_end(&mut contents)?;
+    Ok(contents)
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use super::*;
+use signatures::*;
+use symbolic::*;
+
+/// A recipe, up to symmetry, and the signatures for which it is valid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidityRow {
+    /// The canonical hash, which two rows share only if their hashes collide.
+    pub hash: String,
+    /// Positions in the input of the recipes that are symmetries of each other.
+    pub recipes: Vec<usize>,
+    pub valid: Vec<bool>,
+    pub universal: bool
+}
+
+/// Validity of recipes for the representative tuple of each signature.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidityMatrix {
+    pub signatures: Vec<Signature>,
+    pub representatives: Vec<DimensionTuple>,
+    pub rows: Vec<ValidityRow>
+}
+
+impl ValidityMatrix {
+    pub fn new(recipes: &[Recipe], table: &SignatureTable) -> ValidityMatrix {
+        let mut rows: Vec<ValidityRow> = Vec::new();
+        let mut canonicals: Vec<Recipe> = Vec::new();
+        let mut positions: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, recipe) in recipes.iter().enumerate() {
+            let hash = recipe.canonical_hash();
+            let canonical = recipe.canonical();
+            let position = positions.get(&hash).and_then(|positions| {
+                positions.iter().find(|&&position| canonicals[position] == canonical)
+            });
+            if let Some(&position) = position {
+                rows[position].recipes.push(i);
+                continue
+            }
+            let conditions: Vec<Condition> = RecipeBuilder::generate(recipe, vec!()).validity_conditions();
+            let valid = table.entries.iter().map(|entry| {
+                conditions.iter().all(|condition| condition.holds(&entry.representative))
+            }).collect();
+            positions.entry(hash.clone()).or_default().push(rows.len());
+            canonicals.push(canonical);
+            rows.push(ValidityRow { hash, recipes: vec!(i), valid, universal: recipe.is_universal() });
+        }
+        ValidityMatrix {
+            signatures: table.entries.iter().map(|entry| entry.signature.clone()).collect(),
+            representatives: table.representatives(),
+            rows
+        }
+    }
+
+    /// Column labels, with `*` for the empty signature of `n <= 3`.
+    fn labels(&self) -> Vec<String> {
+        self.signatures.iter().map(|signature| {
+            if signature.is_empty() { String::from("*") } else { signature_str(signature) }
+        }).collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("hash,recipes,{},universal\n", self.labels().join(","));
+        for row in &self.rows {
+            let recipes: Vec<String> = row.recipes.iter().map(|i| i.to_string()).collect();
+            let valid: Vec<&str> = row.valid.iter().map(|&v| if v { "1" } else { "0" }).collect();
+            csv.push_str(&format!("{},{},{},{}\n", row.hash, recipes.join(" "), valid.join(","), if row.universal { 1 } else { 0 }));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let signatures: Vec<String> = self.labels().iter().zip(self.representatives.iter()).map(|(label, representative)| {
+            format!("{{ \"signature\": \"{}\", \"representative\": {:?} }}", label, representative)
+        }).collect();
+        let rows: Vec<String> = self.rows.iter().map(|row| {
+            format!("{{ \"hash\": \"{}\", \"recipes\": {:?}, \"valid\": {:?}, \"universal\": {} }}",
+                row.hash, row.recipes, row.valid, row.universal)
+        }).collect();
+        format!("{{ \"signatures\": [{}],\n\"recipes\": [\n{}\n] }}", signatures.join(", "), rows.join(",\n"))
+    }
+
+    pub fn save_csv(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_csv(), &format!("exports/{}", directory), &format!("{}.csv", file_name))
+          .expect("Error writing validity matrix to file");
+    }
+
+    pub fn save_json(&self, directory: &String, file_name: &String) {
+        utils::write_file(&self.to_json(), &format!("exports/{}", directory), &format!("{}.json", file_name))
+          .expect("Error writing validity matrix to file");
+    }
+}
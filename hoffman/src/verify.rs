@@ -0,0 +1,79 @@
+use std::fmt;
+
+use super::*;
+
+/// Evidence that a list of boxes is a packing of the cube.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Certificate<T> {
+    pub box_count: usize,
+    pub side: T,
+    /// Sum of the volumes of the boxes.
+    pub volume: T,
+    pub cube_volume: T,
+    /// The part of the cube left empty, `cube_volume - volume`, which only a
+    /// tuple of equal sides closes.
+    pub gap: T,
+    /// Number of pairs of boxes whose projections on the first axis meet, and so were compared in full.
+    pub comparisons: usize
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerificationError<T> {
+    Overlap(Coord, Coord),
+    OutsideCube(Coord),
+    Volume { volume: T, cube_volume: T }
+}
+
+impl<T: fmt::Display> fmt::Display for VerificationError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerificationError::Overlap(coord, other) => write!(f, "Boxes at {:?} and {:?} overlap.", coord, other),
+            VerificationError::OutsideCube(coord) => write!(f, "Box at {:?} is not inside the cube.", coord),
+            VerificationError::Volume { volume, cube_volume } => write!(f, "Volume {} exceeds the cube volume {}.", volume, cube_volume)
+        }
+    }
+}
+
+/// Checks that boxes lie in the cube `[0, side]^m` with disjoint interiors,
+/// independently of the grid they were built on.
+///
+/// The boxes are swept along the first axis, and every box is compared with
+/// all boxes still open at its beginning, so no pair is missed however far
+/// apart their grid coordinates are. Boxes with a zero side have no interior
+/// and are only checked to lie in the cube. Volumes are summed exactly in `T`,
+/// as a check on the rest that does not depend on the sweep, and the gap they
+/// leave in the cube is recorded.
+pub fn verify_packing<T: Number>(boxes: &[(Coord, HyperRectangle<T>)], side: &T) -> Result<Certificate<T>, VerificationError<T>> {
+    let m = boxes.first().map(|(_, hyper_rectangle)| hyper_rectangle.len()).unwrap_or(0);
+    for (coord, hyper_rectangle) in boxes {
+        if hyper_rectangle.iter().any(|interval| interval.begin < T::zero() || &interval.end > side || interval.begin > interval.end) {
+            return Err(VerificationError::OutsideCube(coord.clone()))
+        }
+    }
+
+    let mut order: Vec<usize> = (0..boxes.len()).filter(|&i| !boxes[i].1.iter().any(|interval| interval.is_degenerate())).collect();
+    order.sort_by(|&i, &j| boxes[i].1[0].begin.cmp(&boxes[j].1[0].begin));
+    let mut active: Vec<usize> = Vec::new();
+    let mut comparisons = 0;
+    for &i in &order {
+        let hyper_rectangle = &boxes[i].1;
+        active.retain(|&j| boxes[j].1[0].end > hyper_rectangle[0].begin);
+        for &j in &active {
+            comparisons += 1;
+            if hyper_rectangle.iter().zip(boxes[j].1.iter()).all(|(a, b)| a.overlaps(b)) {
+                return Err(VerificationError::Overlap(boxes[j].0.clone(), boxes[i].0.clone()))
+            }
+        }
+        active.push(i);
+    }
+
+    let volume = boxes.iter().fold(T::zero(), |acc, (_, hyper_rectangle)| {
+        acc + hyper_rectangle.iter().skip(1).fold(hyper_rectangle[0].width(), |product, interval| product * interval.width())
+    });
+    let cube_volume = (1..m).fold(side.clone(), |product, _| product * side.clone());
+    if volume > cube_volume {
+        return Err(VerificationError::Volume { volume, cube_volume })
+    }
+    let gap = cube_volume.clone() - volume.clone();
+    Ok(Certificate { box_count: boxes.len(), side: side.clone(), volume, cube_volume, gap, comparisons })
+}
@@ -1,28 +1,21 @@
 extern crate hoffman;
 
 use hoffman::*;
+use hoffman::sweep::*;
+use hoffman::archive::*;
 
 fn main() {
-    let recipes: Vec<Recipe> = (0..21).map(|i| {
-        Recipe::load_json(&String::from("res"), &format!("3D Packing {}", i))
-    }).collect();
-    let limit = 100;
-    for a in 1..limit {
-        for b in a..limit {
-            for c in b..limit {
-                let dimension_tuple = vec!(a, b, c);
-                if !validate_recipes(&recipes, &dimension_tuple) {
-                    panic!("Counter-example using dimension tuple {:?}!", dimension_tuple);
-                }
-            }
-        }
-        println!("({}, _, _) dimension tuples passed.", a);
+    let archive = RecipeArchive::open(&String::from("res"), &String::from("3d")).expect("Error opening archive.");
+    let recipes: Vec<Recipe> = archive.recipes().expect("Error reading archive.");
+    let mut sweep = Sweep::new(TupleSource::Range { n: 3, limit: 100 });
+    sweep.checkpoint = Some((String::from("exports/sweeps"), String::from("3d.json")));
+    let report = sweep.run(&recipes, |report| {
+        println!("{} dimension tuples checked, {} failures.", report.checked, report.failures.len());
+    }).unwrap_or_else(|error| panic!("{}", error));
+    for failure in &report.failures {
+        println!("{}", failure);
+    }
+    if report.failures.is_empty() {
+        println!("All dimension tuples passed.");
     }
-}
-
-fn validate_recipes(recipes: &Vec<Recipe>, dimension_tuple: &DimensionTuple) -> bool {
-    recipes.iter().all(|recipe| {
-        let recipe_builder = RecipeBuilder::generate(recipe, vec!(dimension_tuple.clone()));
-        recipe_builder.validate()
-    })
 }
@@ -1,35 +1,24 @@
 extern crate hoffman;
 
 use hoffman::*;
+use hoffman::sweep::*;
+use hoffman::archive::*;
 use std::time::Instant;
 
 fn main() {
-    let recipes: Vec<Recipe> = (1..=900).map(|i| {
-        Recipe::load_json(&String::from("res"), &format!("cube-4d-{}", i))
-    }).collect();
+    let archive = RecipeArchive::open(&String::from("res"), &String::from("cube-4d")).expect("Error opening archive.");
+    let mut recipes: Vec<Recipe> = archive.recipes().expect("Error reading archive.");
+    recipes.truncate(900);
 
-    let limit = 50;
     let start = Instant::now();
-    for a in 1..limit {
-        for b in a..limit {
-            let sub_time = Instant::now();
-            for c in b..limit {
-                for d in c..limit {
-                    let dimension_tuple = vec!(a, b, c, d);
-                    if !validate_recipes(&recipes, &dimension_tuple) {
-                        panic!("Counter-example using dimension tuple {:?}!", dimension_tuple);
-                    }
-                }
-            }
-            println!("({}, {}, _, _) dimension tuples passed in {} seconds.", a, b, sub_time.elapsed().as_secs());
-        }
+    let mut sweep = Sweep::new(TupleSource::Range { n: 4, limit: 50 });
+    sweep.checkpoint = Some((String::from("exports/sweeps"), String::from("cubes-4d.json")));
+    let report = sweep.run(&recipes, |report| {
+        println!("{} dimension tuples checked, {} failures after {} seconds.",
+            report.checked, report.failures.len(), start.elapsed().as_secs());
+    }).unwrap_or_else(|error| panic!("{}", error));
+    for failure in &report.failures {
+        println!("{}", failure);
     }
-    println!("All dimension tuples passed in {} seconds.", start.elapsed().as_secs());
-}
-
-fn validate_recipes(recipes: &Vec<Recipe>, dimension_tuple: &DimensionTuple) -> bool {
-    recipes.iter().all(|recipe| {
-        let recipe_builder = RecipeBuilder::generate(recipe, vec!(dimension_tuple.clone()));
-        recipe_builder.validate()
-    })
+    println!("{} of {} dimension tuples checked in {} seconds.", report.checked, report.position, start.elapsed().as_secs());
 }